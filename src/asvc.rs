@@ -1,108 +1,239 @@
-/// NOTE: variable naming is based on notation in https://eprint.iacr.org/2020/527.pdf
+//! NOTE: variable naming is based on notation in https://eprint.iacr.org/2020/527.pdf
 
 use std::ops::{Mul, Div};
 use ark_ff::Field;
-use ark_ec::pairing::Pairing;
-use crate::utils::{get_omega, mul, div, scalar_mul, interpolate, evaluate};
-
-#[derive(Clone)]
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate};
+use crate::commitment::{Commitment, Proof};
+use crate::error::KzgError;
+use crate::kzg::check_multi_opening;
+use crate::polynomial::DensePolynomial;
+use crate::trusted_setup::TrustedSetup;
+use crate::utils::{msm, EvaluationDomain, Fk20Precomputation};
+
+// CRS and commitment keys are stored in affine form for multi-scalar multiplication
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct CRS<E: Pairing> {
-    pub g1: Vec<E::G1>,
-    pub g2: Vec<E::G2>
+    pub g1: Vec<E::G1Affine>,
+    pub g2: Vec<E::G2Affine>
 }
 
-// NOTE: currently not in use (update function not implemented yet)
-#[derive(Clone)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct UpdateKey<E: Pairing> {
-    pub ai_commitment: Vec<E::G1>,
-    pub ui_commitment: Vec<E::G1>
+    pub ai_commitment: Vec<E::G1Affine>,
+    pub ui_commitment: Vec<E::G1Affine>
 }
 
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct ProvingKey<E: Pairing> {
     pub crs: CRS<E>,
     pub update_key: UpdateKey<E>,
-    pub li_commitment: Vec<E::G1>
+    pub li_commitment: Vec<E::G1Affine>
 }
 
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct VerificationKey<E: Pairing> {
     pub crs: CRS<E>,
     pub a_commitment: E::G1
 }
 
+// domain and the first powers of the CRS for verifying openings of up to max_positions positions at once,
+// trimmed from an ASVC instance
+#[derive(Clone, CanonicalSerialize)]
+pub struct VectorVerifierKey<E: Pairing> {
+    pub degree: usize,
+    pub domain: EvaluationDomain<E::ScalarField>,
+    pub crs: CRS<E>
+}
+
+impl <E: Pairing> Valid for VectorVerifierKey<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.crs.check()?;
+
+        // max_positions powers in G1 and max_positions + 1 in G2, for at least one and at most n positions
+        if self.degree == 0 || EvaluationDomain::<E::ScalarField>::new(self.degree).ok() != Some(self.domain) {
+            return Err(SerializationError::InvalidData);
+        }
+        if self.crs.g1.is_empty() || self.crs.g2.len() != self.crs.g1.len() + 1 || self.crs.g1.len() > self.domain.size {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl <E: Pairing> CanonicalDeserialize for VectorVerifierKey<E> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let key = Self {
+            degree: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            domain: EvaluationDomain::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            crs: CRS::deserialize_with_mode(&mut reader, compress, Validate::No)?
+        };
+        if validate == Validate::Yes {
+            key.check()?;
+        }
+        Ok(key)
+    }
+}
+
+impl <E: Pairing> VectorVerifierKey<E> {
+    // verify a subvector commitment
+    pub fn verify_position(
+        &self,
+        commitment: Commitment<E>,
+        indices: &[usize],
+        subvector: &[E::ScalarField],
+        pi: Proof<E>
+    ) -> Result<bool, KzgError> {
+        check_position_opening(self.degree, &self.domain, &self.crs, commitment, indices, subvector, pi)
+    }
+}
+
+// deserialization checks that the domain and all keys match the number of positions
+#[derive(Clone, CanonicalSerialize)]
 pub struct ASVC<E: Pairing> {
+    // number of positions in the vector
     pub degree: usize,
+    // evaluation domain generated by omega, position i of the vector is evaluated at w^i
+    // (vectors shorter than the domain are padded with zeros)
+    pub domain: EvaluationDomain<E::ScalarField>,
     pub update_key: UpdateKey<E>,
     pub proving_key: ProvingKey<E>,
     pub verification_key: VerificationKey<E>
 }
 
-impl <E: Pairing> ASVC<E> {
-    pub fn key_gen(g1: E::G1, g2: E::G2, degree: usize, secret: E::ScalarField) -> Self {
-        // set up common reference string
-        let mut crs_g1: Vec<E::G1> = Vec::new();
-        let mut crs_g2: Vec<E::G2> = Vec::new();
-        for i in 0..degree+1 {
-            crs_g1.push(g1.mul(secret.pow(&[i as u64])));
-            crs_g2.push(g2.mul(secret.pow(&[i as u64])));
-        }
-
-        // a_commitment is X^n - 1 multiply by G1
-        let a_commitment: E::G1 = crs_g1[degree].mul(E::ScalarField::ONE) + crs_g1[0].mul(-E::ScalarField::ONE);
+impl <E: Pairing> Valid for ASVC<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.update_key.check()?;
+        self.proving_key.check()?;
+        self.verification_key.check()?;
 
-        // ai_commitment is (X^n - 1) / (X - w^i) multiply by G1
-        let mut ai_commitment = vec![g1; degree];
+        // the domain is the smallest one with a position for every value
+        if self.degree == 0 || EvaluationDomain::<E::ScalarField>::new(self.degree).ok() != Some(self.domain) {
+            return Err(SerializationError::InvalidData);
+        }
 
-        // li_commitment is Lagrange basis for point i, multiply by G1
-        let mut li_commitment = vec![g1; degree];
+        // every position has its keys, and the CRS has n + 1 powers in G1 and at least [1]_2 in G2
+        let n = self.domain.size;
+        let keys = [
+            &self.update_key.ai_commitment,
+            &self.update_key.ui_commitment,
+            &self.proving_key.update_key.ai_commitment,
+            &self.proving_key.update_key.ui_commitment,
+            &self.proving_key.li_commitment
+        ];
+        if keys.iter().any(|key| key.len() != self.degree) {
+            return Err(SerializationError::InvalidData);
+        }
+        for crs in [&self.proving_key.crs, &self.verification_key.crs] {
+            if crs.g1.len() != n + 1 || crs.g2.is_empty() || crs.g2.len() > n + 1 {
+                return Err(SerializationError::InvalidData);
+            }
+        }
+        Ok(())
+    }
+}
 
-        // ui_commitment is the KZG proofs for lagrage basis for point i
-        let mut ui_commitment = vec![g1; degree];
+impl <E: Pairing> CanonicalDeserialize for ASVC<E> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let asvc = Self {
+            degree: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            domain: EvaluationDomain::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            update_key: UpdateKey::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            proving_key: ProvingKey::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            verification_key: VerificationKey::deserialize_with_mode(&mut reader, compress, Validate::No)?
+        };
+        if validate == Validate::Yes {
+            asvc.check()?;
+        }
+        Ok(asvc)
+    }
+}
 
-        // ai_numerator is X^n - 1
-        let mut ai_numerator = vec![E::ScalarField::ZERO; degree+1];
-        ai_numerator[0] = -E::ScalarField::ONE;
-        ai_numerator[degree] = E::ScalarField::ONE;
-        for i in 0..degree {
-            // ai_denominator is X-w^i
-            let ai_denominator = vec![-get_omega(&vec![E::ScalarField::ZERO; degree]).pow([i as u64]), E::ScalarField::ONE];
-            let ai_polynomial = div(&ai_numerator, &ai_denominator).unwrap();
+impl <E: Pairing> ASVC<E> {
+    // generate the CRS with n+1 powers of the secret, and derive the keys from it
+    pub fn key_gen(g1: E::G1, g2: E::G2, degree: usize, secret: E::ScalarField) -> Result<Self, KzgError> {
+        // vectors are padded with zeros to the size n of the smallest power-of-two domain
+        let n = EvaluationDomain::<E::ScalarField>::new(degree)?.size;
 
-            // li_polynomial is ai_polynomial / a'(w^i), where a'(w^i) = n * (w^i)
-            let li_polynomial = scalar_mul(
-                &ai_polynomial,
-                (get_omega(&vec![E::ScalarField::ZERO; degree]).pow([i as u64])).div(E::ScalarField::from(degree as u32))
-            );
+        // set up common reference string
+        let mut crs_g1: Vec<E::G1> = Vec::new();
+        let mut crs_g2: Vec<E::G2> = Vec::new();
+        for i in 0..n+1 {
+            crs_g1.push(g1.mul(secret.pow([i as u64])));
+            crs_g2.push(g2.mul(secret.pow([i as u64])));
+        }
 
-            // ui_polynomial is (li_polynomial - 1) / (X - w^i) 
-            let mut ui_numerator = li_polynomial.clone();
-            ui_numerator[0] = ui_numerator[0] - E::ScalarField::ONE;
-            let ui_polynomial = div(&ui_numerator, &ai_denominator).unwrap();
+        Self::from_crs(
+            CRS {
+                g1: E::G1::normalize_batch(&crs_g1),
+                g2: E::G2::normalize_batch(&crs_g2)
+            },
+            degree
+        )
+    }
 
-            // commit according to crs_g1
-            ai_commitment[i] = crs_g1.iter().zip(ai_polynomial.iter())
-                .map(|(crs, ai_coeff)| crs.mul(ai_coeff))
-                .fold(g1.mul(E::ScalarField::ZERO), |acc, element| acc + element);
+    // derive the keys from powers of tau produced by a ceremony, without knowing the secret
+    pub fn from_trusted_setup(setup: &TrustedSetup<E>, degree: usize) -> Result<Self, KzgError> {
+        Self::from_crs(
+            CRS {
+                g1: setup.g1_powers.clone(),
+                g2: setup.g2_powers.clone()
+            },
+            degree
+        )
+    }
 
-            li_commitment[i] = crs_g1.iter().zip(li_polynomial.iter())
-                .map(|(crs, li_coeff)| crs.mul(li_coeff))
-                .fold(g1.mul(E::ScalarField::ZERO), |acc, element| acc + element);
+    // derive the keys from a common reference string with at least n+1 powers in G1
+    pub(crate) fn from_crs(mut crs: CRS<E>, degree: usize) -> Result<Self, KzgError> {
+        // vector needs at least one position
+        if degree == 0 {
+            return Err(KzgError::EmptyInput);
+        }
 
-            ui_commitment[i] = crs_g1.iter().zip(ui_polynomial.iter())
-                .map(|(crs, ui_coeff)| crs.mul(ui_coeff))
-                .fold(g1.mul(E::ScalarField::ZERO), |acc, element| acc + element);
+        // vectors are padded with zeros to the size n of the smallest power-of-two domain
+        let domain = EvaluationDomain::<E::ScalarField>::new(degree)?;
+        let n = domain.size;
+        if crs.g1.len() < n+1 {
+            return Err(KzgError::DegreeTooLarge { degree: n, max_degree: crs.g1.len().saturating_sub(1) });
         }
+        crs.g1.truncate(n+1);
+        crs.g2.truncate(n+1);
+        let crs_g1 = &crs.g1;
+
+        // a_commitment is X^n - 1 multiply by G1
+        let a_commitment: E::G1 = crs_g1[n].mul(E::ScalarField::ONE) + crs_g1[0].mul(-E::ScalarField::ONE);
+
+        // li_commitment is Lagrange basis for point i, multiply by G1, i.e. [L_i(tau)]_1 = 1/n sum_j w^(-ij) [tau^j]_1
+        // is the inverse FFT of the first n powers (only the first degree positions need keys as padding is
+        // never updated)
+        let powers: Vec<E::G1> = crs_g1[..n].iter().map(|&point| point.into()).collect();
+        let mut li_commitment = domain.group_ifft(&powers)?;
+        li_commitment.truncate(degree);
+
+        // ai_commitment is (X^n - 1) / (X - w^i) multiply by G1, which is L_i(X) * a'(w^i) with a'(w^i) = n / w^i
+        let ai_commitment: Vec<E::G1> = li_commitment.iter().enumerate()
+            .map(|(i, li)| li.mul(E::ScalarField::from(n as u64) / domain.element(i)))
+            .collect();
+
+        // ui_commitment is the KZG proofs for lagrage basis for point i, (L_i(X) - 1) / (X - w^i), whose
+        // coefficient j is (n - 1 - j) w^(-i(j+1)) / n, so all of them are w^(-i) times the inverse FFT
+        // of (n - 1 - j) [tau^j]_1
+        let scaled_powers: Vec<E::G1> = powers.iter().enumerate()
+            .map(|(j, power)| power.mul(E::ScalarField::from((n - 1 - j) as u64)))
+            .collect();
+        let ui_commitment: Vec<E::G1> = domain.group_ifft(&scaled_powers)?.into_iter().take(degree).enumerate()
+            .map(|(i, ui)| ui.mul(domain.element(i).inverse().unwrap()))
+            .collect();
 
         let update_key = UpdateKey {
-            ai_commitment,
-            ui_commitment
-        };
-        let crs = CRS {
-            g1: crs_g1,
-            g2: crs_g2
+            ai_commitment: E::G1::normalize_batch(&ai_commitment),
+            ui_commitment: E::G1::normalize_batch(&ui_commitment)
         };
+        let li_commitment = E::G1::normalize_batch(&li_commitment);
 
-        Self {
+        Ok(Self {
             degree,
+            domain,
             update_key: update_key.clone(),
             proving_key: ProvingKey {
                 crs: crs.clone(),
@@ -113,107 +244,207 @@ impl <E: Pairing> ASVC<E> {
                 crs: crs.clone(),
                 a_commitment
             }
-        }
+        })
     }
 
     // commit the lagrange polynomial of the vector
-    pub fn vector_commit(&self, vector: &[E::ScalarField]) -> E::G1 {
+    pub fn vector_commit(&self, vector: &[E::ScalarField]) -> Result<Commitment<E>, KzgError> {
         // check that vector length is equal to l_commitment length
-        assert_eq!(vector.len(), self.proving_key.li_commitment.len());
+        self.check_length(vector.len())?;
 
         // commit vector
-        let mut commitment = self.proving_key.crs.g1[0].mul(E::ScalarField::ZERO);
-        for i in 0..vector.len() {
-            commitment += self.proving_key.li_commitment[i] * vector[i]
-        }
-        commitment
+        Ok(Commitment(msm::<E::G1>(&self.proving_key.li_commitment, vector)))
     }
 
     // prove multiple positions in the vector
-    pub fn prove_position(&self, indices: &[usize], vector: &[E::ScalarField]) -> E::G1 {
-        // numerator is lagrage interpolation of the vector
-        let points: Vec<E::ScalarField> = (0..vector.len()).map(|i| E::ScalarField::from(i as u32)).collect();
-        let numerator = interpolate(&points, &vector).unwrap();
+    pub fn prove_position(&self, indices: &[usize], vector: &[E::ScalarField]) -> Result<Proof<E>, KzgError> {
+        // check that the vector has one value per position
+        self.check_length(vector.len())?;
+
+        // numerator is lagrange interpolation of the vector (padded with zeros) over the evaluation domain
+        let numerator = DensePolynomial::from_coefficients_vec(self.domain.ifft(vector)?);
 
         // denominator is product of i in indices (X - w^i)
-        let omega = get_omega(&vec![E::ScalarField::ZERO; vector.len()]);
-        let mut denominator = vec![-omega.pow([indices[0] as u64]), E::ScalarField::ONE];
-        for i in 1..indices.len() {
-            denominator = mul(&denominator, &vec![-omega.pow([i as u64]), E::ScalarField::ONE]);
-        }
+        let denominator = self.vanishing_polynomial(indices)?;
 
         // quotient is numerator divided by denominator, commited by G1
-        let quotient = div(&numerator, &denominator).unwrap();
-        let mut pi = self.proving_key.crs.g1[0] * quotient[0];
-        for i in 1..quotient.len() {
-            pi += self.proving_key.crs.g1[i] * quotient[i];
+        // (the remainder of the division is the lagrange interpolation of the subvector)
+        let (quotient, _) = numerator.divide_with_remainder(&denominator)?;
+        Ok(Proof(msm::<E::G1>(&self.proving_key.crs.g1, &quotient)))
+    }
+
+    // FFTs of the CRS that prove_all_positions needs, computed once and reused for every vector
+    pub fn fk20_precomputation(&self) -> Result<Fk20Precomputation<E::G1>, KzgError> {
+        Fk20Precomputation::new(&self.proving_key.crs.g1, self.domain.size - 1, 1)
+    }
+
+    // prove every single position of the vector at once with FK20, i.e. the i-th proof is the same as
+    // prove_position(&[i], vector), given the precomputation of these keys
+    pub fn prove_all_positions(
+        &self,
+        vector: &[E::ScalarField],
+        precomputation: &Fk20Precomputation<E::G1>
+    ) -> Result<Vec<Proof<E>>, KzgError> {
+        // check that the vector has one value per position
+        self.check_length(vector.len())?;
+        if precomputation.coset_size != 1 {
+            return Err(KzgError::LengthMismatch { expected: 1, actual: precomputation.coset_size });
         }
 
-        pi     
+        // lagrange interpolation of the vector (padded with zeros) over the evaluation domain
+        let poly = self.domain.ifft(vector)?;
+
+        // proofs of the padding positions are dropped
+        let mut proofs = precomputation.proofs(&poly, &self.domain)?;
+        proofs.truncate(self.degree);
+        Ok(proofs.into_iter().map(Proof).collect())
     }
 
     // verify a subvector commitment
     pub fn verify_position(
         &self,
-        commitment: E::G1,
+        commitment: Commitment<E>,
         indices: &[usize],
         subvector: &[E::ScalarField],
-        pi: E::G1
-    ) -> bool {
-        // denominator is product of i in indices (X - w^i)
-        let omega = get_omega(&vec![E::ScalarField::ZERO; subvector.len()]);
-        let mut denominator = vec![-omega.pow([indices[0] as u64]), E::ScalarField::ONE];
-        for i in 1..indices.len() {
-            denominator = mul(&denominator, &vec![-omega.pow([i as u64]), E::ScalarField::ONE]);
-        }
+        pi: Proof<E>
+    ) -> Result<bool, KzgError> {
+        check_position_opening(self.degree, &self.domain, &self.verification_key.crs, commitment, indices, subvector, pi)
+    }
 
-        // commit denominator
-        let mut denominator_commitment = self.verification_key.crs.g2[0].mul(E::ScalarField::ZERO);
-        for i in 0..denominator.len() {
-            denominator_commitment += self.verification_key.crs.g2[i].mul(denominator[i]);
+    // verifier key that holds the powers to verify openings of up to max_positions positions at once
+    pub fn verifier_key(&self, max_positions: usize) -> Result<VectorVerifierKey<E>, KzgError> {
+        let crs = &self.verification_key.crs;
+        if max_positions == 0 {
+            return Err(KzgError::EmptyInput);
         }
-
-        // remainer is the product of the lagrange basis of the indices
-        let indices_field: Vec<E::ScalarField> = indices.iter().map(|&i| E::ScalarField::from(i as u32)).collect();
-        let remainder = interpolate(&indices_field, &subvector).unwrap();
-
-        // commit remainder
-        let mut remainder_commitment = self.verification_key.crs.g1[0].mul(E::ScalarField::ZERO);
-        for i in 0..remainder.len() {
-            remainder_commitment += self.verification_key.crs.g1[i].mul(remainder[i]);
+        if max_positions >= crs.g2.len() {
+            return Err(KzgError::DegreeTooLarge { degree: max_positions, max_degree: crs.g2.len() - 1 });
         }
 
-        // verification
-        let lhs = E::pairing(pi, denominator_commitment);
-        let rhs = E::pairing(commitment - remainder_commitment, self.verification_key.crs.g2[0]);
-        lhs == rhs
+        Ok(VectorVerifierKey {
+            degree: self.degree,
+            domain: self.domain,
+            crs: CRS {
+                g1: crs.g1[..max_positions].to_vec(),
+                g2: crs.g2[..max_positions + 1].to_vec()
+            }
+        })
     }
 
     // aggregate multiple proofs into one subvector commitment
-    pub fn aggregate_proofs(&self, indices: &[usize], proofs: Vec<E::G1>) -> E::G1 {
+    pub fn aggregate_proofs(&self, indices: &[usize], proofs: Vec<Proof<E>>) -> Result<Proof<E>, KzgError> {
         // make sure that length of indices is the same as proofs
-        assert_eq!(indices.len(), proofs.len());
+        if indices.len() != proofs.len() {
+            return Err(KzgError::LengthMismatch { expected: indices.len(), actual: proofs.len() });
+        }
 
         // A(X) is product of i in indices (X - w^i)
-        let omega = get_omega(&vec![E::ScalarField::ZERO; indices.len()]);
-        let mut a_polynomial = vec![-omega.pow([indices[0] as u64]), E::ScalarField::ONE];
-        for i in 1..indices.len() {
-            a_polynomial = mul(&a_polynomial, &vec![-omega.pow([i as u64]), E::ScalarField::ONE]);
-        }
+        let a_polynomial = self.vanishing_polynomial(indices)?;
 
         // A'(X), derivatives of A(X)
-        let mut a_derivative = vec![E::ScalarField::ZERO; a_polynomial.len() - 1];
-        for i in 1..a_polynomial.len() {
-            a_derivative[i - 1] = a_polynomial[i] * E::ScalarField::from(i as u32);
+        let a_derivative = a_polynomial.derivative();
+
+        // pi is the sum of proofs scaled by c_i = 1 / A'(w^i), A'(w^i) is non-zero as indices are distinct
+        let scalars: Vec<E::ScalarField> = indices.iter()
+            .map(|&i| a_derivative.evaluate(self.domain.element(i)).inverse().unwrap())
+            .collect();
+        Proof::linear_combination(&proofs, &scalars)
+    }
+
+    // update the vector commitment after the value at index changes by delta
+    pub fn update_commitment(&self, commitment: Commitment<E>, index: usize, delta: E::ScalarField) -> Result<Commitment<E>, KzgError> {
+        // make sure that index is a position in the vector
+        check_index(index, self.degree)?;
+
+        // C' = C + delta * l_i, as only the lagrange basis for point i is affected
+        Ok(commitment + Commitment(self.proving_key.li_commitment[index].mul(delta)))
+    }
+
+    // update a proof for proof_index after the value at changed_index changes by delta
+    pub fn update_proof(
+        &self,
+        proof: Proof<E>,
+        proof_index: usize,
+        changed_index: usize,
+        delta: E::ScalarField
+    ) -> Result<Proof<E>, KzgError> {
+        // make sure that both indices are positions in the vector
+        check_index(proof_index, self.degree)?;
+        check_index(changed_index, self.degree)?;
+
+        // same index: pi' = pi + delta * u_i, where u_i is the KZG proof of the lagrange basis for point i
+        if proof_index == changed_index {
+            return Ok(proof + Proof(self.update_key.ui_commitment[proof_index].mul(delta)));
         }
 
-        let pi = indices.iter().enumerate().map(|(k, &i)|{
-            proofs[k].mul(evaluate(&a_derivative, omega.pow([i as u64])))
-        }).sum::<E::G1>();
+        // different index: pi' = pi + delta * u_ij, where u_ij is the KZG proof of the lagrange basis
+        // for point j evaluated at point i, i.e. u_ij = (a_j - a_i) * w^j / (n * (w^j - w^i))
+        let omega_i = self.domain.element(proof_index);
+        let omega_j = self.domain.element(changed_index);
+        let c = omega_j.div(E::ScalarField::from(self.domain.size as u64) * (omega_j - omega_i));
+        let uij_commitment = self.update_key.ai_commitment[changed_index].into_group() - self.update_key.ai_commitment[proof_index];
+
+        Ok(proof + Proof(uij_commitment.mul(c * delta)))
+    }
+
+    // domain elements w^i for i in indices
+    fn domain_elements(&self, indices: &[usize]) -> Vec<E::ScalarField> {
+        indices.iter().map(|&i| self.domain.element(i)).collect()
+    }
+
+    // vanishing polynomial of the subdomain, i.e. product of i in indices (X - w^i)
+    fn vanishing_polynomial(&self, indices: &[usize]) -> Result<DensePolynomial<E::ScalarField>, KzgError> {
+        check_indices(indices, self.degree)?;
+        Ok(DensePolynomial::from_roots(&self.domain_elements(indices)))
+    }
+
+    // check that a vector has exactly one value per position
+    fn check_length(&self, length: usize) -> Result<(), KzgError> {
+        if length != self.degree {
+            return Err(KzgError::LengthMismatch { expected: self.degree, actual: length });
+        }
+        Ok(())
+    }
+}
 
-        pi
+// check an opening of the positions at indices, which is a KZG multi point opening at w^i for i in indices
+fn check_position_opening<E: Pairing>(
+    degree: usize,
+    domain: &EvaluationDomain<E::ScalarField>,
+    crs: &CRS<E>,
+    commitment: Commitment<E>,
+    indices: &[usize],
+    subvector: &[E::ScalarField],
+    pi: Proof<E>
+) -> Result<bool, KzgError> {
+    // make sure that every index has a corresponding value in the subvector
+    if indices.len() != subvector.len() {
+        return Err(KzgError::LengthMismatch { expected: indices.len(), actual: subvector.len() });
     }
+    check_indices(indices, degree)?;
+
+    let points: Vec<E::ScalarField> = indices.iter().map(|&i| domain.element(i)).collect();
+    check_multi_opening::<E>(crs.g2[0].into_group(), &crs.g1, &crs.g2, &points, subvector, commitment, pi)
+}
 
-    // TODO: update commmitment and proofs functions
+// check that indices are distinct positions in the vector
+fn check_indices(indices: &[usize], degree: usize) -> Result<(), KzgError> {
+    if indices.is_empty() {
+        return Err(KzgError::EmptyInput);
+    }
+    for (k, &i) in indices.iter().enumerate() {
+        check_index(i, degree)?;
+        if indices[..k].contains(&i) {
+            return Err(KzgError::DuplicatePoints);
+        }
+    }
+    Ok(())
+}
 
-}
\ No newline at end of file
+// check that index is a position in the vector
+fn check_index(index: usize, degree: usize) -> Result<(), KzgError> {
+    if index >= degree {
+        return Err(KzgError::IndexOutOfRange { index, length: degree });
+    }
+    Ok(())
+}
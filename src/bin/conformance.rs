@@ -0,0 +1,203 @@
+//! Runs the KZG test vectors of the Ethereum consensus specs against the blob and cell API.
+//!
+//! Usage: `cargo run --features conformance --bin conformance -- <trusted_setup.txt> <tests dir>`
+//!
+//! Every `<handler>/<suite>/<case>/data.yaml` below the tests directory is a case, e.g. the
+//! `tests/general/deneb/kzg` and `tests/general/fulu/kzg` directories of consensus-spec-tests. A
+//! null output means that the inputs are invalid and the function must return an error.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use ark_bls12_381::Bls12_381;
+use serde_yaml::Value;
+use kzg_rust::{Eip4844, KzgError, TrustedSetup};
+use kzg_rust::encoding::{decode_hex, encode_hex};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <trusted_setup.txt> <tests dir>", args[0]);
+        return ExitCode::FAILURE;
+    }
+
+    let eip4844 = match TrustedSetup::<Bls12_381>::load(&args[1]).and_then(|setup| Eip4844::from_trusted_setup(&setup)) {
+        Ok(eip4844) => eip4844,
+        Err(error) => {
+            eprintln!("cannot load trusted setup {}: {}", args[1], error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut cases = vec![];
+    if let Err(error) = find_cases(Path::new(&args[2]), &mut cases) {
+        eprintln!("cannot read tests dir {}: {}", args[2], error);
+        return ExitCode::FAILURE;
+    }
+    cases.sort();
+
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    for case in &cases {
+        // the case is named after its handler, suite and case directories
+        let case_dir = case.parent().unwrap();
+        let handler = case_dir.parent().and_then(Path::parent).and_then(Path::file_name)
+            .and_then(|name| name.to_str()).unwrap_or_default();
+        let name = case_dir.components().rev().take(3).collect::<Vec<_>>().into_iter().rev()
+            .map(|component| component.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+
+        match run_case(&eip4844, handler, case) {
+            Ok(Some(())) => {
+                passed += 1;
+                println!("PASS {}", name);
+            }
+            Ok(None) => {
+                skipped += 1;
+                println!("SKIP {}", name);
+            }
+            Err(reason) => {
+                failed += 1;
+                println!("FAIL {}: {}", name, reason);
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} skipped", passed, failed, skipped);
+    if failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+// collect the data.yaml files below a directory
+fn find_cases(dir: &Path, cases: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_cases(&path, cases)?;
+        } else if path.file_name().is_some_and(|name| name == "data.yaml") {
+            cases.push(path);
+        }
+    }
+    Ok(())
+}
+
+// run a case, returning None if the handler is not supported and the reason if the case fails
+fn run_case(eip4844: &Eip4844, handler: &str, path: &Path) -> Result<Option<()>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let data: Value = serde_yaml::from_str(&contents).map_err(|e| e.to_string())?;
+    let input = data.get("input").ok_or("missing input")?;
+    let expected = data.get("output").ok_or("missing output")?;
+
+    let output = match handler {
+        "blob_to_kzg_commitment" => {
+            let blob = field(input, "blob")?;
+            decode_hex(blob)
+                .and_then(|blob| eip4844.blob_to_kzg_commitment(&blob))
+                .map(|commitment| hex_value(&commitment))
+        }
+        "compute_kzg_proof" => {
+            let (blob, z) = (field(input, "blob")?, field(input, "z")?);
+            decode_hex(blob)
+                .and_then(|blob| eip4844.compute_kzg_proof(&blob, &decode_hex(z)?))
+                .map(|(proof, y)| Value::Sequence(vec![hex_value(&proof), hex_value(&y)]))
+        }
+        "compute_blob_kzg_proof" => {
+            let (blob, commitment) = (field(input, "blob")?, field(input, "commitment")?);
+            decode_hex(blob)
+                .and_then(|blob| eip4844.compute_blob_kzg_proof(&blob, &decode_hex(commitment)?))
+                .map(|proof| hex_value(&proof))
+        }
+        "verify_kzg_proof" => {
+            let (commitment, z) = (field(input, "commitment")?, field(input, "z")?);
+            let (y, proof) = (field(input, "y")?, field(input, "proof")?);
+            decode_hex(commitment)
+                .and_then(|commitment| eip4844.verify_kzg_proof(
+                    &commitment, &decode_hex(z)?, &decode_hex(y)?, &decode_hex(proof)?
+                ))
+                .map(Value::Bool)
+        }
+        "verify_blob_kzg_proof" => {
+            let (blob, commitment, proof) = (field(input, "blob")?, field(input, "commitment")?, field(input, "proof")?);
+            decode_hex(blob)
+                .and_then(|blob| eip4844.verify_blob_kzg_proof(&blob, &decode_hex(commitment)?, &decode_hex(proof)?))
+                .map(Value::Bool)
+        }
+        "verify_blob_kzg_proof_batch" => {
+            let (blobs, commitments) = (list(input, "blobs")?, list(input, "commitments")?);
+            let proofs = list(input, "proofs")?;
+            decode_hex_list(&blobs)
+                .and_then(|blobs| eip4844.verify_blob_kzg_proof_batch(
+                    &blobs, &decode_hex_list(&commitments)?, &decode_hex_list(&proofs)?
+                ))
+                .map(Value::Bool)
+        }
+        "compute_cells" => {
+            let blob = field(input, "blob")?;
+            decode_hex(blob)
+                .and_then(|blob| eip4844.compute_cells_and_kzg_proofs(&blob))
+                .map(|(cells, _)| hex_list_value(&cells))
+        }
+        "compute_cells_and_kzg_proofs" => {
+            let blob = field(input, "blob")?;
+            decode_hex(blob)
+                .and_then(|blob| eip4844.compute_cells_and_kzg_proofs(&blob))
+                .map(|(cells, proofs)| Value::Sequence(vec![hex_list_value(&cells), hex_list_value(&proofs)]))
+        }
+        "verify_cell_kzg_proof_batch" => {
+            let (commitments, cell_indices) = (list(input, "commitments")?, indices(input, "cell_indices")?);
+            let (cells, proofs) = (list(input, "cells")?, list(input, "proofs")?);
+            decode_hex_list(&commitments)
+                .and_then(|commitments| eip4844.verify_cell_kzg_proof_batch(
+                    &commitments, &cell_indices, &decode_hex_list(&cells)?, &decode_hex_list(&proofs)?
+                ))
+                .map(Value::Bool)
+        }
+        "recover_cells_and_kzg_proofs" => {
+            let (cell_indices, cells) = (indices(input, "cell_indices")?, list(input, "cells")?);
+            decode_hex_list(&cells)
+                .and_then(|cells| eip4844.recover_cells_and_kzg_proofs(&cell_indices, &cells))
+                .map(|(cells, proofs)| Value::Sequence(vec![hex_list_value(&cells), hex_list_value(&proofs)]))
+        }
+        _ => return Ok(None),
+    };
+
+    match (output, expected) {
+        (Err(_), Value::Null) => Ok(Some(())),
+        (Err(error), _) => Err(format!("unexpected error: {}", error)),
+        (Ok(output), _) if output == lowercase(expected) => Ok(Some(())),
+        (Ok(output), _) => Err(format!("expected {:?} but got {:?}", expected, output)),
+    }
+}
+
+fn field<'a>(input: &'a Value, key: &str) -> Result<&'a str, String> {
+    input.get(key).and_then(Value::as_str).ok_or(format!("missing input {}", key))
+}
+
+fn list<'a>(input: &'a Value, key: &str) -> Result<Vec<&'a str>, String> {
+    input.get(key).and_then(Value::as_sequence)
+        .and_then(|values| values.iter().map(Value::as_str).collect())
+        .ok_or(format!("missing input {}", key))
+}
+
+fn indices(input: &Value, key: &str) -> Result<Vec<usize>, String> {
+    input.get(key).and_then(Value::as_sequence)
+        .and_then(|values| values.iter().map(|value| value.as_u64().map(|index| index as usize)).collect())
+        .ok_or(format!("missing input {}", key))
+}
+
+fn decode_hex_list(values: &[&str]) -> Result<Vec<Vec<u8>>, KzgError> {
+    values.iter().map(|value| decode_hex(value)).collect()
+}
+
+fn hex_value(bytes: &[u8]) -> Value {
+    Value::String(encode_hex(bytes))
+}
+
+fn hex_list_value(values: &[impl AsRef<[u8]>]) -> Value {
+    Value::Sequence(values.iter().map(|value| hex_value(value.as_ref())).collect())
+}
+
+// expected outputs are compared as lowercase hex strings
+fn lowercase(value: &Value) -> Value {
+    match value {
+        Value::String(string) => Value::String(string.to_lowercase()),
+        Value::Sequence(values) => Value::Sequence(values.iter().map(lowercase).collect()),
+        value => value.clone(),
+    }
+}
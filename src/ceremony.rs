@@ -0,0 +1,192 @@
+//! Multi-party powers-of-tau ceremony: participants sequentially rescale the powers with their own
+//! secret, so the final tau is unknown unless every participant colludes.
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate};
+use ark_std::rand::RngCore;
+use sha2::{Digest, Sha256};
+use crate::error::KzgError;
+use crate::trusted_setup::TrustedSetup;
+
+// domain separator of the Fiat-Shamir challenge of the proof of knowledge
+const POK_DOMAIN: &[u8] = b"KZG_RUST_CEREMONY_POK_V1";
+
+// proof that a participant knows their secret s and multiplied tau by it
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ContributionProof<E: Pairing> {
+    // [tau]_1 before the contribution
+    pub previous_tau_g1: E::G1Affine,
+    // [tau * s]_1 after the contribution
+    pub tau_g1: E::G1Affine,
+    // [s]_1 and [s]_2, the public key of the contribution
+    pub secret_g1: E::G1Affine,
+    pub secret_g2: E::G2Affine,
+    // Schnorr proof of knowledge of s: commitment [k]_1 and response k + c * s
+    pub pok_commitment: E::G1Affine,
+    pub pok_response: E::ScalarField
+}
+
+// deserialization verifies the whole transcript
+#[derive(Clone, CanonicalSerialize)]
+pub struct Ceremony<E: Pairing> {
+    // current powers of tau
+    pub setup: TrustedSetup<E>,
+    // one proof per contribution, in order
+    pub contributions: Vec<ContributionProof<E>>
+}
+
+impl <E: Pairing> Ceremony<E> {
+    // start a ceremony with num_g1 powers in G1 and num_g2 powers in G2, initially with tau = 1
+    pub fn new(num_g1: usize, num_g2: usize) -> Result<Self, KzgError> {
+        if num_g1 < 2 || num_g2 < 2 {
+            return Err(KzgError::InvalidSetup("at least two G1 and G2 powers are required".to_string()));
+        }
+
+        Ok(Self {
+            setup: TrustedSetup {
+                g1_powers: vec![E::G1Affine::generator(); num_g1],
+                g2_powers: vec![E::G2Affine::generator(); num_g2]
+            },
+            contributions: vec![]
+        })
+    }
+
+    // contribute a fresh random secret s, rescaling [tau^i] to [(tau * s)^i], and record the proof
+    pub fn contribute<R: RngCore>(&mut self, rng: &mut R) -> &ContributionProof<E> {
+        let mut secret = E::ScalarField::rand(rng);
+        while secret.is_zero() {
+            secret = E::ScalarField::rand(rng);
+        }
+
+        // rescale the powers, the i-th power is multiplied by s^i
+        let mut g1_powers = Vec::with_capacity(self.setup.g1_powers.len());
+        let mut g2_powers = Vec::with_capacity(self.setup.g2_powers.len());
+        let mut secret_power = E::ScalarField::ONE;
+        for i in 0..std::cmp::max(self.setup.g1_powers.len(), self.setup.g2_powers.len()) {
+            if let Some(g1_power) = self.setup.g1_powers.get(i) {
+                g1_powers.push(*g1_power * secret_power);
+            }
+            if let Some(g2_power) = self.setup.g2_powers.get(i) {
+                g2_powers.push(*g2_power * secret_power);
+            }
+            secret_power *= secret;
+        }
+        let previous_tau_g1 = self.setup.g1_powers[1];
+        self.setup.g1_powers = E::G1::normalize_batch(&g1_powers);
+        self.setup.g2_powers = E::G2::normalize_batch(&g2_powers);
+
+        // Schnorr proof of knowledge of s, bound to the powers it was applied to
+        let secret_g1 = (E::G1::generator() * secret).into_affine();
+        let k = E::ScalarField::rand(rng);
+        let pok_commitment = (E::G1::generator() * k).into_affine();
+        let challenge = pok_challenge::<E>(&previous_tau_g1, &secret_g1, &pok_commitment);
+
+        self.contributions.push(ContributionProof {
+            previous_tau_g1,
+            tau_g1: self.setup.g1_powers[1],
+            secret_g1,
+            secret_g2: (E::G2::generator() * secret).into_affine(),
+            pok_commitment,
+            pok_response: k + challenge * secret
+        });
+        self.contributions.last().unwrap()
+    }
+
+    // verify the whole transcript: every contribution is a known, non-zero secret applied to the output of
+    // the previous one, starting from tau = 1, and the final powers are consistent powers of the final tau
+    pub fn verify(&self) -> Result<(), KzgError> {
+        let g1 = E::G1Affine::generator();
+        let g2 = E::G2Affine::generator();
+
+        // without any contribution tau is still 1
+        if self.contributions.is_empty() {
+            return Err(KzgError::InvalidSetup("no contributions".to_string()));
+        }
+
+        let mut previous_tau_g1 = g1;
+        for (i, proof) in self.contributions.iter().enumerate() {
+            let invalid = |reason: &str| Err(KzgError::InvalidSetup(format!("contribution {}: {}", i, reason)));
+
+            if proof.previous_tau_g1 != previous_tau_g1 {
+                return invalid("does not build on the previous contribution");
+            }
+            if proof.secret_g1.is_zero() || proof.tau_g1.is_zero() {
+                return invalid("secret must be non-zero");
+            }
+
+            // proof of knowledge: [z]_1 = R + c * [s]_1
+            let challenge = pok_challenge::<E>(&proof.previous_tau_g1, &proof.secret_g1, &proof.pok_commitment);
+            if g1 * proof.pok_response != proof.pok_commitment + proof.secret_g1 * challenge {
+                return invalid("invalid proof of knowledge");
+            }
+
+            // [s]_1 and [s]_2 have the same discrete logarithm: e([s]_1, [1]_2) = e([1]_1, [s]_2)
+            if E::pairing(proof.secret_g1, g2) != E::pairing(g1, proof.secret_g2) {
+                return invalid("inconsistent public key");
+            }
+
+            // tau was multiplied by s: e([tau * s]_1, [1]_2) = e([tau]_1, [s]_2)
+            if E::pairing(proof.tau_g1, g2) != E::pairing(proof.previous_tau_g1, proof.secret_g2) {
+                return invalid("invalid update proof");
+            }
+
+            previous_tau_g1 = proof.tau_g1;
+        }
+
+        // the final powers start at the generators and are powers of the tau of the last contribution
+        if self.setup.g1_powers.first() != Some(&g1) || self.setup.g2_powers.first() != Some(&g2) {
+            return Err(KzgError::InvalidSetup("powers do not start at the generators".to_string()));
+        }
+        if self.setup.g1_powers.get(1) != Some(&previous_tau_g1) {
+            return Err(KzgError::InvalidSetup("powers do not match the last contribution".to_string()));
+        }
+        self.setup.verify()
+    }
+}
+
+impl <E: Pairing> Valid for Ceremony<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.setup.g1_powers.check()?;
+        self.setup.g2_powers.check()?;
+        self.contributions.check()?;
+
+        // a ceremony that has not started yet has every power at the generators (tau = 1)
+        if self.contributions.is_empty() {
+            let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+            let powers = &self.setup;
+            if powers.g1_powers.len() >= 2 && powers.g2_powers.len() >= 2
+                && powers.g1_powers.iter().all(|&point| point == g1)
+                && powers.g2_powers.iter().all(|&point| point == g2) {
+                return Ok(());
+            }
+        }
+        self.verify().map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+impl <E: Pairing> CanonicalDeserialize for Ceremony<E> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let ceremony = Self {
+            setup: TrustedSetup::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            contributions: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?
+        };
+        if validate == Validate::Yes {
+            ceremony.check()?;
+        }
+        Ok(ceremony)
+    }
+}
+
+// Fiat-Shamir challenge of the proof of knowledge, hashing the previous tau, the public key and the commitment
+fn pok_challenge<E: Pairing>(
+    previous_tau_g1: &E::G1Affine,
+    secret_g1: &E::G1Affine,
+    pok_commitment: &E::G1Affine
+) -> E::ScalarField {
+    let mut bytes = POK_DOMAIN.to_vec();
+    for point in [previous_tau_g1, secret_g1, pok_commitment] {
+        point.serialize_compressed(&mut bytes).unwrap();
+    }
+    E::ScalarField::from_le_bytes_mod_order(&Sha256::digest(&bytes))
+}
@@ -0,0 +1,85 @@
+//! Typed commitments and opening proofs, so that one cannot be passed where the other is expected. Both
+//! are G1 points and inherit their homomorphism: the commitment to a * p + b * q is a * C_p + b * C_q,
+//! and the same holds for the proofs of p and q at the same point.
+
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use crate::error::KzgError;
+use crate::utils::msm;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<E: Pairing>(pub E::G1);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<E: Pairing>(pub E::G1);
+
+// group operations of a wrapper around a G1 point, and linear combinations of many wrappers
+macro_rules! impl_g1_wrapper {
+    ($name:ident) => {
+        impl <E: Pairing> $name<E> {
+            // sum of scalars[i] * values[i]
+            pub fn linear_combination(values: &[Self], scalars: &[E::ScalarField]) -> Result<Self, KzgError> {
+                if scalars.len() != values.len() {
+                    return Err(KzgError::LengthMismatch { expected: values.len(), actual: scalars.len() });
+                }
+                let points: Vec<E::G1> = values.iter().map(|value| value.0).collect();
+                Ok(Self(msm::<E::G1>(&E::G1::normalize_batch(&points), scalars)))
+            }
+        }
+
+        impl <E: Pairing> Add for $name<E> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self(self.0 + other.0)
+            }
+        }
+
+        impl <E: Pairing> Sub for $name<E> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self(self.0 - other.0)
+            }
+        }
+
+        impl <E: Pairing> Neg for $name<E> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl <E: Pairing> Mul<E::ScalarField> for $name<E> {
+            type Output = Self;
+
+            fn mul(self, scalar: E::ScalarField) -> Self {
+                Self(self.0 * scalar)
+            }
+        }
+
+        impl <E: Pairing> AddAssign for $name<E> {
+            fn add_assign(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+        }
+
+        impl <E: Pairing> SubAssign for $name<E> {
+            fn sub_assign(&mut self, other: Self) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl <E: Pairing> Sum for $name<E> {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|value| value.0).sum())
+            }
+        }
+    };
+}
+
+impl_g1_wrapper!(Commitment);
+impl_g1_wrapper!(Proof);
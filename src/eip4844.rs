@@ -0,0 +1,220 @@
+//! Blob commitments of EIP-4844 (Deneb) on top of the BLS12-381 `KZG` instantiation. A blob is a
+//! polynomial in evaluation form over the roots of unity of order 4096 in bit-reversed order, encoded
+//! as 32-byte big-endian field elements. Commitments and proofs are 48-byte compressed G1 points.
+
+use std::sync::OnceLock;
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective as G1};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInt, BigInteger, Field, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+use crate::commitment::{Commitment, Proof};
+use crate::error::KzgError;
+use crate::kzg::KZG;
+use crate::polynomial::DensePolynomial;
+use crate::trusted_setup::TrustedSetup;
+use crate::utils::{bit_reverse_permutation, EvaluationDomain, Fk20Precomputation};
+
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+pub const BYTES_PER_COMMITMENT: usize = 48;
+pub const BYTES_PER_PROOF: usize = 48;
+
+// domain separators of the Fiat-Shamir challenges
+pub const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"FSBLOBVERIFY_V1_";
+pub const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8] = b"RCKZGBATCH___V1_";
+
+pub type KzgCommitment = [u8; BYTES_PER_COMMITMENT];
+pub type KzgProof = [u8; BYTES_PER_PROOF];
+pub type Bytes32 = [u8; BYTES_PER_FIELD_ELEMENT];
+
+#[derive(Clone)]
+pub struct Eip4844 {
+    pub kzg: KZG<Bls12_381>,
+    pub domain: EvaluationDomain<Fr>,
+    // FFTs of the CRS for the proofs of all cells of a blob (EIP-7594), computed on first use so that
+    // the blob API does not pay for them
+    pub(crate) cell_precomputation: OnceLock<Fk20Precomputation<G1>>,
+}
+
+impl Eip4844 {
+    // wrap a KZG instance whose CRS has at least FIELD_ELEMENTS_PER_BLOB powers in G1
+    pub fn new(kzg: KZG<Bls12_381>) -> Result<Self, KzgError> {
+        if kzg.crs_g1.is_empty() {
+            return Err(KzgError::SetupNotRun);
+        }
+        if kzg.crs_g1.len() < FIELD_ELEMENTS_PER_BLOB {
+            return Err(KzgError::DegreeTooLarge { degree: FIELD_ELEMENTS_PER_BLOB - 1, max_degree: kzg.degree });
+        }
+
+        Ok(Self {
+            kzg,
+            domain: EvaluationDomain::new(FIELD_ELEMENTS_PER_BLOB)?,
+            cell_precomputation: OnceLock::new(),
+        })
+    }
+
+    // build an instance from the powers of tau of the Ethereum KZG ceremony
+    pub fn from_trusted_setup(setup: &TrustedSetup<Bls12_381>) -> Result<Self, KzgError> {
+        Self::new(KZG::from_trusted_setup(setup)?)
+    }
+
+    pub fn blob_to_kzg_commitment(&self, blob: &[u8]) -> Result<KzgCommitment, KzgError> {
+        let poly = self.blob_to_polynomial(blob)?;
+        Ok(g1_to_bytes(self.kzg.commit(&poly)?.0))
+    }
+
+    // prove the evaluation of the blob at z, returning the proof and the evaluation y
+    pub fn compute_kzg_proof(&self, blob: &[u8], z: &[u8]) -> Result<(KzgProof, Bytes32), KzgError> {
+        let poly = self.blob_to_polynomial(blob)?;
+        let z = bytes_to_bls_field(z)?;
+        let proof = self.kzg.open(&poly, z)?;
+        Ok((g1_to_bytes(proof.0), bls_field_to_bytes(poly.evaluate(z))))
+    }
+
+    // prove the evaluation of the blob at the Fiat-Shamir challenge derived from the blob and its commitment
+    pub fn compute_blob_kzg_proof(&self, blob: &[u8], commitment: &[u8]) -> Result<KzgProof, KzgError> {
+        // the commitment must be a valid point even though only its bytes are hashed
+        let _ = bytes_to_kzg_commitment(commitment)?;
+        let poly = self.blob_to_polynomial(blob)?;
+        let z = compute_challenge(blob, commitment);
+        Ok(g1_to_bytes(self.kzg.open(&poly, z)?.0))
+    }
+
+    pub fn verify_kzg_proof(&self, commitment: &[u8], z: &[u8], y: &[u8], proof: &[u8]) -> Result<bool, KzgError> {
+        let commitment = bytes_to_kzg_commitment(commitment)?;
+        let z = bytes_to_bls_field(z)?;
+        let y = bytes_to_bls_field(y)?;
+        let proof = bytes_to_kzg_commitment(proof)?;
+        self.kzg.verify(z, y, Commitment(commitment), Proof(proof))
+    }
+
+    pub fn verify_blob_kzg_proof(&self, blob: &[u8], commitment: &[u8], proof: &[u8]) -> Result<bool, KzgError> {
+        let commitment_point = bytes_to_kzg_commitment(commitment)?;
+        let proof = bytes_to_kzg_commitment(proof)?;
+        let poly = self.blob_to_polynomial(blob)?;
+        let z = compute_challenge(blob, commitment);
+        self.kzg.verify(z, poly.evaluate(z), Commitment(commitment_point), Proof(proof))
+    }
+
+    // verify many blob proofs at once, combining them with powers of a challenge derived from all inputs
+    pub fn verify_blob_kzg_proof_batch(
+        &self,
+        blobs: &[impl AsRef<[u8]>],
+        commitments: &[impl AsRef<[u8]>],
+        proofs: &[impl AsRef<[u8]>]
+    ) -> Result<bool, KzgError> {
+        if commitments.len() != blobs.len() {
+            return Err(KzgError::LengthMismatch { expected: blobs.len(), actual: commitments.len() });
+        }
+        if proofs.len() != blobs.len() {
+            return Err(KzgError::LengthMismatch { expected: blobs.len(), actual: proofs.len() });
+        }
+
+        // random challenge over all commitments, evaluations and proofs
+        let mut data = RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.to_vec();
+        data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+        data.extend_from_slice(&(blobs.len() as u64).to_be_bytes());
+
+        let mut commitment_points = Vec::with_capacity(blobs.len());
+        let mut proof_points = Vec::with_capacity(blobs.len());
+        let mut zs = Vec::with_capacity(blobs.len());
+        let mut ys = Vec::with_capacity(blobs.len());
+        for ((blob, commitment), proof) in blobs.iter().zip(commitments).zip(proofs) {
+            let (blob, commitment, proof) = (blob.as_ref(), commitment.as_ref(), proof.as_ref());
+            commitment_points.push(bytes_to_kzg_commitment(commitment)?.into_affine());
+            proof_points.push(bytes_to_kzg_commitment(proof)?.into_affine());
+            let poly = self.blob_to_polynomial(blob)?;
+            let z = compute_challenge(blob, commitment);
+            let y = poly.evaluate(z);
+
+            data.extend_from_slice(commitment);
+            data.extend_from_slice(&bls_field_to_bytes(z));
+            data.extend_from_slice(&bls_field_to_bytes(y));
+            data.extend_from_slice(proof);
+            zs.push(z);
+            ys.push(y);
+        }
+        let r = hash_to_bls_field(&data);
+        let r_powers: Vec<Fr> = (0..blobs.len()).map(|i| r.pow([i as u64])).collect();
+
+        // e(sum r^i proof_i, [tau]_2) = e(sum r^i (commitment_i - [y_i]_1 + z_i proof_i), [1]_2)
+        let proof_lincomb = G1::msm_unchecked(&proof_points, &r_powers);
+        let r_zs: Vec<Fr> = r_powers.iter().zip(&zs).map(|(r_power, z)| *r_power * z).collect();
+        let r_ys: Fr = r_powers.iter().zip(&ys).map(|(r_power, y)| *r_power * y).sum();
+        let rhs = G1::msm_unchecked(&commitment_points, &r_powers)
+            + G1::msm_unchecked(&proof_points, &r_zs)
+            - self.kzg.g1 * r_ys;
+
+        let check = Bls12_381::multi_pairing(
+            [proof_lincomb.into_affine(), (-rhs).into_affine()],
+            [self.kzg.g2_tau.into_affine(), self.kzg.g2.into_affine()]
+        );
+        Ok(check.is_zero())
+    }
+
+    // decode a blob into the coefficients of its polynomial
+    pub(crate) fn blob_to_polynomial(&self, blob: &[u8]) -> Result<DensePolynomial<Fr>, KzgError> {
+        if blob.len() != BYTES_PER_BLOB {
+            return Err(KzgError::LengthMismatch { expected: BYTES_PER_BLOB, actual: blob.len() });
+        }
+
+        // evaluations are stored in bit-reversed order of the domain
+        let mut evals = blob.chunks(BYTES_PER_FIELD_ELEMENT)
+            .map(bytes_to_bls_field)
+            .collect::<Result<Vec<Fr>, KzgError>>()?;
+        bit_reverse_permutation(&mut evals);
+        Ok(DensePolynomial::from_coefficients_vec(self.domain.ifft(&evals)?))
+    }
+}
+
+// decode a 32-byte big-endian field element, rejecting non-canonical encodings
+pub fn bytes_to_bls_field(bytes: &[u8]) -> Result<Fr, KzgError> {
+    if bytes.len() != BYTES_PER_FIELD_ELEMENT {
+        return Err(KzgError::LengthMismatch { expected: BYTES_PER_FIELD_ELEMENT, actual: bytes.len() });
+    }
+
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        *limb = u64::from_be_bytes(bytes[24 - 8 * i..32 - 8 * i].try_into().unwrap());
+    }
+    Fr::from_bigint(BigInt::new(limbs))
+        .ok_or(KzgError::InvalidEncoding("field element is not less than the modulus".to_string()))
+}
+
+pub fn bls_field_to_bytes(value: Fr) -> Bytes32 {
+    value.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+// decode a 48-byte compressed G1 point (the point at infinity included), checking subgroup membership
+pub fn bytes_to_kzg_commitment(bytes: &[u8]) -> Result<G1, KzgError> {
+    if bytes.len() != BYTES_PER_COMMITMENT {
+        return Err(KzgError::LengthMismatch { expected: BYTES_PER_COMMITMENT, actual: bytes.len() });
+    }
+    // the point at infinity has a single encoding, the compression and infinity flags followed by zeros
+    if bytes[0] & 0x40 != 0 && (bytes[0] != 0xc0 || bytes[1..].iter().any(|&byte| byte != 0)) {
+        return Err(KzgError::InvalidEncoding("non-canonical encoding of the point at infinity".to_string()));
+    }
+    Ok(G1Affine::deserialize_compressed(bytes)?.into_group())
+}
+
+pub fn g1_to_bytes(point: G1) -> KzgCommitment {
+    let mut bytes = [0u8; BYTES_PER_COMMITMENT];
+    point.into_affine().serialize_compressed(&mut bytes[..]).unwrap();
+    bytes
+}
+
+// big-endian hash of the data reduced modulo the field order
+pub(crate) fn hash_to_bls_field(data: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(&Sha256::digest(data))
+}
+
+// Fiat-Shamir challenge of a blob proof, hashing the blob and its commitment
+fn compute_challenge(blob: &[u8], commitment: &[u8]) -> Fr {
+    let mut data = FIAT_SHAMIR_PROTOCOL_DOMAIN.to_vec();
+    data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes());
+    data.extend_from_slice(blob);
+    data.extend_from_slice(commitment);
+    hash_to_bls_field(&data)
+}
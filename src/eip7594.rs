@@ -0,0 +1,255 @@
+//! Cells of EIP-7594 (PeerDAS) on top of the EIP-4844 blob API. A blob is extended 2x with Reed-Solomon
+//! to 8192 evaluations in bit-reversed order, which are split into 128 cells of 64 evaluations. Each cell
+//! is a coset of the 64-th roots of unity and comes with a KZG multiproof of the blob at that coset.
+
+use std::collections::HashSet;
+use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, Zero};
+use crate::eip4844::{
+    bls_field_to_bytes, bytes_to_bls_field, bytes_to_kzg_commitment, g1_to_bytes, hash_to_bls_field, Eip4844, KzgProof,
+    BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB
+};
+use crate::error::KzgError;
+use crate::polynomial::DensePolynomial;
+use crate::utils::{bit_reverse_permutation, EvaluationDomain, Fk20Precomputation};
+
+pub const FIELD_ELEMENTS_PER_EXT_BLOB: usize = 2 * FIELD_ELEMENTS_PER_BLOB;
+pub const FIELD_ELEMENTS_PER_CELL: usize = 64;
+pub const BYTES_PER_CELL: usize = FIELD_ELEMENTS_PER_CELL * BYTES_PER_FIELD_ELEMENT;
+pub const CELLS_PER_EXT_BLOB: usize = FIELD_ELEMENTS_PER_EXT_BLOB / FIELD_ELEMENTS_PER_CELL;
+
+// domain separator of the random challenge of batch cell verification
+pub const RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN: &[u8] = b"RCKZGCBATCH__V1_";
+
+// multiplicative generator of the field, used as the shift of the coset FFT during recovery
+const PRIMITIVE_ROOT_OF_UNITY: u64 = 7;
+
+pub type Cell = [u8; BYTES_PER_CELL];
+
+impl Eip4844 {
+    // extend the blob and prove every cell of the extension
+    pub fn compute_cells_and_kzg_proofs(&self, blob: &[u8]) -> Result<(Vec<Cell>, Vec<KzgProof>), KzgError> {
+        let poly = self.blob_to_polynomial(blob)?;
+        self.compute_cells_and_kzg_proofs_polynomial(&poly)
+    }
+
+    // verify cells of possibly different blobs at once, combining them with powers of a challenge derived
+    // from all inputs, i.e. e(sum r^k proof_k, [tau^64]_2) = e(sum r^k (commitment_k - [I_k(tau)]_1 + h_k^64 proof_k), [1]_2)
+    // where I_k interpolates cell k over its coset h_k * <w^128>
+    pub fn verify_cell_kzg_proof_batch(
+        &self,
+        commitments: &[impl AsRef<[u8]>],
+        cell_indices: &[usize],
+        cells: &[impl AsRef<[u8]>],
+        proofs: &[impl AsRef<[u8]>]
+    ) -> Result<bool, KzgError> {
+        if commitments.len() != cells.len() {
+            return Err(KzgError::LengthMismatch { expected: cells.len(), actual: commitments.len() });
+        }
+        if cell_indices.len() != cells.len() {
+            return Err(KzgError::LengthMismatch { expected: cells.len(), actual: cell_indices.len() });
+        }
+        if proofs.len() != cells.len() {
+            return Err(KzgError::LengthMismatch { expected: cells.len(), actual: proofs.len() });
+        }
+        if cells.is_empty() {
+            return Ok(true);
+        }
+        let tau_cell_g2 = self.tau_cell_g2()?;
+
+        // the same commitment usually appears for many cells, so it is decoded and hashed only once
+        let mut unique_commitments: Vec<&[u8]> = vec![];
+        let mut commitment_indices = Vec::with_capacity(cells.len());
+        for commitment in commitments {
+            let commitment = commitment.as_ref();
+            match unique_commitments.iter().position(|&unique| unique == commitment) {
+                Some(index) => commitment_indices.push(index),
+                None => {
+                    commitment_indices.push(unique_commitments.len());
+                    unique_commitments.push(commitment);
+                }
+            }
+        }
+        let commitment_points = unique_commitments.iter()
+            .map(|commitment| Ok(bytes_to_kzg_commitment(commitment)?.into_affine()))
+            .collect::<Result<Vec<_>, KzgError>>()?;
+        let proof_points = proofs.iter()
+            .map(|proof| Ok(bytes_to_kzg_commitment(proof.as_ref())?.into_affine()))
+            .collect::<Result<Vec<_>, KzgError>>()?;
+        let cosets_evals = cells.iter()
+            .map(|cell| cell_to_coset_evals(cell.as_ref()))
+            .collect::<Result<Vec<_>, KzgError>>()?;
+        for &cell_index in cell_indices {
+            check_cell_index(cell_index)?;
+        }
+
+        // random challenge over all commitments, cells and proofs
+        let mut data = RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN.to_vec();
+        data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+        data.extend_from_slice(&(FIELD_ELEMENTS_PER_CELL as u64).to_be_bytes());
+        data.extend_from_slice(&(unique_commitments.len() as u64).to_be_bytes());
+        data.extend_from_slice(&(cells.len() as u64).to_be_bytes());
+        for commitment in &unique_commitments {
+            data.extend_from_slice(commitment);
+        }
+        for k in 0..cells.len() {
+            data.extend_from_slice(&(commitment_indices[k] as u64).to_be_bytes());
+            data.extend_from_slice(&(cell_indices[k] as u64).to_be_bytes());
+            for eval in &cosets_evals[k] {
+                data.extend_from_slice(&bls_field_to_bytes(*eval));
+            }
+            data.extend_from_slice(proofs[k].as_ref());
+        }
+        let r = hash_to_bls_field(&data);
+        let r_powers: Vec<Fr> = (0..cells.len()).map(|k| r.pow([k as u64])).collect();
+
+        // sum of the commitments weighted by the powers of r of their cells
+        let mut commitment_weights = vec![Fr::ZERO; unique_commitments.len()];
+        for (k, &index) in commitment_indices.iter().enumerate() {
+            commitment_weights[index] += r_powers[k];
+        }
+        let commitment_lincomb = G1::msm_unchecked(&commitment_points, &commitment_weights);
+
+        // sum of the interpolation polynomials of the cells weighted by the powers of r
+        let cell_domain = EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_CELL)?;
+        let mut interpolation_poly = DensePolynomial::zero();
+        let mut shifted_weights = Vec::with_capacity(cells.len());
+        for k in 0..cells.len() {
+            let shift = coset_shift(cell_indices[k])?;
+            let mut evals = cosets_evals[k].clone();
+            bit_reverse_permutation(&mut evals);
+            let coeffs = cell_domain.coset_ifft(&evals, shift)?;
+            interpolation_poly += &(DensePolynomial::from_coefficients_vec(coeffs) * r_powers[k]);
+            shifted_weights.push(r_powers[k] * shift.pow([FIELD_ELEMENTS_PER_CELL as u64]));
+        }
+        let interpolation_commitment = self.kzg.commit(&interpolation_poly)?.0;
+
+        let proof_lincomb = G1::msm_unchecked(&proof_points, &r_powers);
+        let rhs = commitment_lincomb - interpolation_commitment + G1::msm_unchecked(&proof_points, &shifted_weights);
+        let check = Bls12_381::multi_pairing(
+            [proof_lincomb.into_affine(), (-rhs).into_affine()],
+            [tau_cell_g2, self.kzg.g2.into_affine()]
+        );
+        Ok(check.is_zero())
+    }
+
+    // recover all cells and their proofs of a blob from at least half of the cells
+    pub fn recover_cells_and_kzg_proofs(
+        &self,
+        cell_indices: &[usize],
+        cells: &[impl AsRef<[u8]>]
+    ) -> Result<(Vec<Cell>, Vec<KzgProof>), KzgError> {
+        if cell_indices.len() != cells.len() {
+            return Err(KzgError::LengthMismatch { expected: cells.len(), actual: cell_indices.len() });
+        }
+        if cells.len() < CELLS_PER_EXT_BLOB / 2 {
+            return Err(KzgError::LengthMismatch { expected: CELLS_PER_EXT_BLOB / 2, actual: cells.len() });
+        }
+        for &cell_index in cell_indices {
+            check_cell_index(cell_index)?;
+        }
+        if cell_indices.iter().collect::<HashSet<_>>().len() != cell_indices.len() {
+            return Err(KzgError::DuplicatePoints);
+        }
+        // as in the consensus specs, the cells must be given in ascending order of their indices
+        if !cell_indices.windows(2).all(|w| w[0] < w[1]) {
+            return Err(KzgError::InvalidEncoding("cell indices are not in ascending order".to_string()));
+        }
+
+        // extended evaluations with the missing cells set to zero, in natural order
+        let mut extended_evals = vec![Fr::ZERO; FIELD_ELEMENTS_PER_EXT_BLOB];
+        for (&cell_index, cell) in cell_indices.iter().zip(cells) {
+            let start = cell_index * FIELD_ELEMENTS_PER_CELL;
+            extended_evals[start..start + FIELD_ELEMENTS_PER_CELL].copy_from_slice(&cell_to_coset_evals(cell.as_ref())?);
+        }
+        bit_reverse_permutation(&mut extended_evals);
+
+        // Z vanishes on the cosets of the missing cells: the product of X^64 - h^64 over their shifts h
+        let mut zero_poly = DensePolynomial::constant(Fr::ONE);
+        for cell_index in (0..CELLS_PER_EXT_BLOB).filter(|index| !cell_indices.contains(index)) {
+            let shift_power = coset_shift(cell_index)?.pow([FIELD_ELEMENTS_PER_CELL as u64]);
+            let mut factor = vec![Fr::ZERO; FIELD_ELEMENTS_PER_CELL + 1];
+            factor[0] = -shift_power;
+            factor[FIELD_ELEMENTS_PER_CELL] = Fr::ONE;
+            zero_poly = zero_poly * DensePolynomial::from_coefficients_vec(factor);
+        }
+
+        // E * Z agrees with P * Z on the whole domain, so P = (E * Z) / Z where the division is done
+        // over a coset of the domain on which Z does not vanish
+        let extended_domain = EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_EXT_BLOB)?;
+        let zero_evals = extended_domain.fft(&zero_poly)?;
+        let product_evals: Vec<Fr> = extended_evals.iter().zip(&zero_evals).map(|(eval, zero)| *eval * zero).collect();
+        let product_poly = extended_domain.ifft(&product_evals)?;
+
+        let offset = Fr::from(PRIMITIVE_ROOT_OF_UNITY);
+        let product_coset_evals = extended_domain.coset_fft(&product_poly, offset)?;
+        let zero_coset_evals = extended_domain.coset_fft(&zero_poly, offset)?;
+        let quotient_coset_evals = product_coset_evals.iter().zip(&zero_coset_evals)
+            .map(|(product, zero)| zero.inverse().map(|zero_inv| *product * zero_inv).ok_or(KzgError::DivisionByZero))
+            .collect::<Result<Vec<Fr>, KzgError>>()?;
+        let mut poly = extended_domain.coset_ifft(&quotient_coset_evals, offset)?;
+        poly.truncate(FIELD_ELEMENTS_PER_BLOB);
+
+        self.compute_cells_and_kzg_proofs_polynomial(&poly)
+    }
+
+    fn compute_cells_and_kzg_proofs_polynomial(&self, poly: &[Fr]) -> Result<(Vec<Cell>, Vec<KzgProof>), KzgError> {
+        // evaluations over the extended domain in bit-reversed order, i.e. cell after cell
+        let extended_domain = EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_EXT_BLOB)?;
+        let mut extended_evals = extended_domain.fft(poly)?;
+        bit_reverse_permutation(&mut extended_evals);
+        let cells = extended_evals.chunks(FIELD_ELEMENTS_PER_CELL)
+            .map(|evals| {
+                let mut cell = [0u8; BYTES_PER_CELL];
+                for (bytes, eval) in cell.chunks_mut(BYTES_PER_FIELD_ELEMENT).zip(evals) {
+                    bytes.copy_from_slice(&bls_field_to_bytes(*eval));
+                }
+                cell
+            })
+            .collect();
+
+        // the coset of cell i is {x : x^64 = w^brp(i)} for w of order 128, so all proofs are computed at once
+        // with FK20 over the 128 cosets, in bit-reversed order like the cells
+        let coset_domain = EvaluationDomain::<Fr>::new(CELLS_PER_EXT_BLOB)?;
+        let mut proofs = self.cell_precomputation()?.proofs(poly, &coset_domain)?;
+        bit_reverse_permutation(&mut proofs);
+        Ok((cells, proofs.into_iter().map(g1_to_bytes).collect()))
+    }
+
+    // FFTs of the CRS for FK20 over the cells, computed the first time cell proofs are needed
+    fn cell_precomputation(&self) -> Result<&Fk20Precomputation<G1>, KzgError> {
+        if let Some(precomputation) = self.cell_precomputation.get() {
+            return Ok(precomputation);
+        }
+        let precomputation = Fk20Precomputation::new(&self.kzg.crs_g1, FIELD_ELEMENTS_PER_BLOB - 1, FIELD_ELEMENTS_PER_CELL)?;
+        Ok(self.cell_precomputation.get_or_init(|| precomputation))
+    }
+
+    // [tau^64]_2, the commitment to the vanishing polynomial of a cell (up to its shift)
+    fn tau_cell_g2(&self) -> Result<<Bls12_381 as Pairing>::G2Affine, KzgError> {
+        self.kzg.crs_g2.get(FIELD_ELEMENTS_PER_CELL).copied()
+            .ok_or(KzgError::DegreeTooLarge { degree: FIELD_ELEMENTS_PER_CELL, max_degree: self.kzg.crs_g2.len() - 1 })
+    }
+}
+
+// the coset of cell i is h * <w^128> with h = w^brp(i), where w generates the extended domain
+fn coset_shift(cell_index: usize) -> Result<Fr, KzgError> {
+    let extended_domain = EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_EXT_BLOB)?;
+    let log_cells = CELLS_PER_EXT_BLOB.trailing_zeros();
+    Ok(extended_domain.element(cell_index.reverse_bits() >> (usize::BITS - log_cells)))
+}
+
+fn cell_to_coset_evals(cell: &[u8]) -> Result<Vec<Fr>, KzgError> {
+    if cell.len() != BYTES_PER_CELL {
+        return Err(KzgError::LengthMismatch { expected: BYTES_PER_CELL, actual: cell.len() });
+    }
+    cell.chunks(BYTES_PER_FIELD_ELEMENT).map(bytes_to_bls_field).collect()
+}
+
+fn check_cell_index(cell_index: usize) -> Result<(), KzgError> {
+    if cell_index >= CELLS_PER_EXT_BLOB {
+        return Err(KzgError::IndexOutOfRange { index: cell_index, length: CELLS_PER_EXT_BLOB });
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Hex encoding of canonically serialized values (compressed, with validation) and serde adapters
+//! built on it, which are available with the `serde` feature.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use crate::error::KzgError;
+
+// encode a value as a 0x prefixed hex string of its compressed serialization
+pub fn to_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    encode_hex(&bytes)
+}
+
+// decode a value from a hex string of its compressed serialization, checking that it is valid
+pub fn from_hex<T: CanonicalDeserialize>(hex: &str) -> Result<T, KzgError> {
+    let bytes = decode_hex(hex)?;
+    let mut reader = bytes.as_slice();
+    let value = T::deserialize_compressed(&mut reader)?;
+    if !reader.is_empty() {
+        return Err(KzgError::InvalidEncoding("trailing bytes".to_string()));
+    }
+    Ok(value)
+}
+
+// encode bytes as a 0x prefixed hex string
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + 2 * bytes.len());
+    hex.push_str("0x");
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+// decode a hex string with an optional 0x prefix
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, KzgError> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(KzgError::InvalidEncoding(format!("invalid hex string {}", hex)));
+    }
+    (0..hex.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16)
+            .map_err(|_| KzgError::InvalidEncoding(format!("invalid hex string {}", hex))))
+        .collect()
+}
+
+// serde adapter for any canonically serializable type, e.g. `#[serde(with = "kzg_rust::encoding::serde_hex")]`
+#[cfg(feature = "serde")]
+pub mod serde_hex {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::to_hex(value))
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        super::from_hex(&hex).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+use ark_serialize::SerializationError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KzgError {
+    // polynomial (or number of points) exceeds what the setup supports
+    DegreeTooLarge { degree: usize, max_degree: usize },
+    // points (or vector indices) to interpolate over are not distinct
+    DuplicatePoints,
+    // index does not refer to a position in the vector
+    IndexOutOfRange { index: usize, length: usize },
+    // inputs that must be paired up have different lengths
+    LengthMismatch { expected: usize, actual: usize },
+    // at least one point (or vector index) is required
+    EmptyInput,
+    // polynomial division by the zero polynomial
+    DivisionByZero,
+    // the common reference string has not been generated yet
+    SetupNotRun,
+    // bytes do not encode a valid element
+    InvalidEncoding(String),
+    // powers of tau loaded from a ceremony are not consistent
+    InvalidSetup(String),
+    // reading a file failed
+    Io(String),
+}
+
+impl fmt::Display for KzgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KzgError::DegreeTooLarge { degree, max_degree } => {
+                write!(f, "degree {} exceeds the maximum supported degree {}", degree, max_degree)
+            }
+            KzgError::DuplicatePoints => write!(f, "points are not distinct"),
+            KzgError::IndexOutOfRange { index, length } => {
+                write!(f, "index {} is out of range for length {}", index, length)
+            }
+            KzgError::LengthMismatch { expected, actual } => {
+                write!(f, "expected length {} but got {}", expected, actual)
+            }
+            KzgError::EmptyInput => write!(f, "at least one point is required"),
+            KzgError::DivisionByZero => write!(f, "cannot divide by zero polynomial"),
+            KzgError::SetupNotRun => write!(f, "setup has not been run"),
+            KzgError::InvalidEncoding(reason) => write!(f, "invalid encoding: {}", reason),
+            KzgError::InvalidSetup(reason) => write!(f, "invalid setup: {}", reason),
+            KzgError::Io(reason) => write!(f, "io error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for KzgError {}
+
+impl From<SerializationError> for KzgError {
+    fn from(error: SerializationError) -> Self {
+        KzgError::InvalidEncoding(error.to_string())
+    }
+}
@@ -0,0 +1,101 @@
+//! Hiding KZG commitments: a polynomial p is committed as [p(tau)]_1 + [gamma r(tau)]_1 for a random
+//! blinding polynomial r, where h = [gamma]_1 is a second generator whose discrete logarithm is unknown.
+//! Openings reveal r(z) along with p(z) and nothing else, as long as there are at most hiding_bound of them.
+
+use std::ops::Mul;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use crate::commitment::{Commitment, Proof};
+use crate::error::KzgError;
+use crate::kzg::KZG;
+use crate::polynomial::DensePolynomial;
+use crate::utils::msm;
+
+// opening proof of a hiding commitment: [w(tau)]_1 + [gamma w_r(tau)]_1 for the quotients w of the
+// polynomial and w_r of the blinding polynomial, and the evaluation of the blinding polynomial
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HidingProof<E: Pairing> {
+    pub w: Proof<E>,
+    pub blinding_value: E::ScalarField
+}
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HidingKZG<E: Pairing> {
+    pub kzg: KZG<E>,
+    // second generator h = [gamma]_1
+    pub h: E::G1,
+    // [gamma * tau^i]_1, the CRS of the blinding polynomials
+    pub crs_h: Vec<E::G1Affine>,
+    // degree of the blinding polynomials, i.e. the number of openings that reveal nothing
+    pub hiding_bound: usize
+}
+
+impl <E: Pairing> HidingKZG<E> {
+    // extend a KZG instance with the powers of a second secret gamma, which must be discarded like tau
+    pub fn setup(kzg: KZG<E>, gamma: E::ScalarField, hiding_bound: usize) -> Result<Self, KzgError> {
+        if kzg.crs_g1.is_empty() {
+            return Err(KzgError::SetupNotRun);
+        }
+        if hiding_bound == 0 {
+            return Err(KzgError::EmptyInput);
+        }
+        if hiding_bound > kzg.degree {
+            return Err(KzgError::DegreeTooLarge { degree: hiding_bound, max_degree: kzg.degree });
+        }
+
+        // only hiding_bound + 1 powers are needed for the blinding polynomials
+        let crs_h: Vec<E::G1> = kzg.crs_g1[..hiding_bound + 1].iter().map(|&point| point.mul(gamma)).collect();
+
+        Ok(Self {
+            h: kzg.g1.mul(gamma),
+            crs_h: E::G1::normalize_batch(&crs_h),
+            kzg,
+            hiding_bound
+        })
+    }
+
+    // commit to the polynomial with a fresh blinding polynomial, which is returned to open the commitment
+    pub fn commit<R: RngCore>(
+        &self,
+        poly: &[E::ScalarField],
+        rng: &mut R
+    ) -> Result<(Commitment<E>, Vec<E::ScalarField>), KzgError> {
+        let blinding_poly: Vec<E::ScalarField> = (0..self.hiding_bound + 1).map(|_| E::ScalarField::rand(rng)).collect();
+        let commitment = self.kzg.commit(poly)? + Commitment(msm::<E::G1>(&self.crs_h, &blinding_poly));
+        Ok((commitment, blinding_poly))
+    }
+
+    pub fn open(
+        &self,
+        poly: &[E::ScalarField],
+        blinding_poly: &[E::ScalarField],
+        point: E::ScalarField
+    ) -> Result<HidingProof<E>, KzgError> {
+        // make sure that the blinding polynomial fits in crs_h
+        if blinding_poly.len() > self.crs_h.len() {
+            return Err(KzgError::DegreeTooLarge { degree: blinding_poly.len() - 1, max_degree: self.hiding_bound });
+        }
+
+        // quotient of the blinding polynomial, the same way KZG::open computes it for the polynomial
+        let blinding_poly = DensePolynomial::from_coefficients_slice(blinding_poly);
+        let blinding_value = blinding_poly.evaluate(point);
+        let numerator = blinding_poly - DensePolynomial::constant(blinding_value);
+        let (blinding_quotient, _) = numerator.divide_with_remainder(&DensePolynomial::from_roots(&[point]))?;
+
+        let w = self.kzg.open(poly, point)? + Proof(msm::<E::G1>(&self.crs_h, &blinding_quotient));
+        Ok(HidingProof { w, blinding_value })
+    }
+
+    // e(w, [tau - z]_2) = e(commitment - [value]_1 - blinding_value h, [1]_2)
+    pub fn verify(
+        &self,
+        point: E::ScalarField,
+        value: E::ScalarField,
+        commitment: Commitment<E>,
+        proof: &HidingProof<E>
+    ) -> Result<bool, KzgError> {
+        self.kzg.verify(point, value, commitment - Commitment(self.h.mul(proof.blinding_value)), proof.w)
+    }
+}
@@ -1,15 +1,56 @@
+use std::collections::HashSet;
 use std::ops::Mul;
-use ark_ff::Field;
-use ark_ec::pairing::Pairing;
-use crate::utils::{div, mul, evaluate, interpolate};
+use ark_ff::{batch_inversion, FftField, Field, PrimeField, Zero};
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate};
+use sha2::{Digest, Sha256};
+use crate::commitment::{Commitment, Proof};
+use crate::error::KzgError;
+use crate::polynomial::DensePolynomial;
+use crate::trusted_setup::TrustedSetup;
+use crate::utils::{msm, EvaluationDomain, Fk20Precomputation};
 
+// a single point opening: commitment, point, value and proof
+pub type Opening<E> = (Commitment<E>, <E as Pairing>::ScalarField, <E as Pairing>::ScalarField, Proof<E>);
+
+// constant-size proof of a multi-polynomial multi-point opening (BDFG20): W = [h]_1 for the quotient
+// h = sum gamma^i (f_i - r_i) Z_{T \ S_i} / Z_T, and W' = [L / (X - z)]_1 for the linearization L at z
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultiPolyProof<E: Pairing> {
+    pub w: E::G1,
+    pub w_prime: E::G1
+}
+
+// deserialization checks that the CRS is consistent with the degree, the generators and the Lagrange basis
+#[derive(Clone, CanonicalSerialize)]
 pub struct KZG<E: Pairing> {
     pub g1: E::G1,
     pub g2: E::G2,
     pub g2_tau: E::G2,
     pub degree: usize,
-    pub crs_g1: Vec<E::G1>,
-    pub crs_g2: Vec<E::G2>,
+    pub crs_g1: Vec<E::G1Affine>,
+    pub crs_g2: Vec<E::G2Affine>,
+    // Lagrange basis [L_i(tau)]_1 over a domain, derived from crs_g1 by setup_lagrange
+    pub lagrange_domain: Option<EvaluationDomain<E::ScalarField>>,
+    pub crs_lagrange: Vec<E::G1Affine>,
+}
+
+// G1 powers for committing to and opening polynomials up to degree, trimmed from a KZG instance
+#[derive(Clone, CanonicalSerialize)]
+pub struct CommitterKey<E: Pairing> {
+    pub degree: usize,
+    pub crs_g1: Vec<E::G1Affine>
+}
+
+// generators and [tau]_2 for verifying openings, trimmed from a KZG instance, with the first powers
+// in G1 and G2 for multi point openings if requested
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierKey<E: Pairing> {
+    pub g1: E::G1,
+    pub g2: E::G2,
+    pub g2_tau: E::G2,
+    pub crs_g1: Vec<E::G1Affine>,
+    pub crs_g2: Vec<E::G2Affine>
 }
 
 impl <E:Pairing> KZG<E> {
@@ -21,127 +62,678 @@ impl <E:Pairing> KZG<E> {
             degree,
             crs_g1: vec![],
             crs_g2: vec![],
+            lagrange_domain: None,
+            crs_lagrange: vec![],
         }
     }
 
-    pub fn setup(&mut self, secret: E::ScalarField) {
-        for i in 0..self.degree+1 {
-            self.crs_g1.push(self.g1.mul(secret.pow(&[i as u64])));
-            self.crs_g2.push(self.g2.mul(secret.pow(&[i as u64])));
+    // build an instance from powers of tau produced by a ceremony, without knowing the secret
+    pub fn from_trusted_setup(setup: &TrustedSetup<E>) -> Result<Self, KzgError> {
+        if setup.g1_powers.len() < 2 || setup.g2_powers.len() < 2 {
+            return Err(KzgError::InvalidSetup("at least two G1 and G2 powers are required".to_string()));
         }
-        self.g2_tau = self.g2.mul(secret);
+
+        Ok(Self {
+            g1: setup.g1_powers[0].into(),
+            g2: setup.g2_powers[0].into(),
+            g2_tau: setup.g2_powers[1].into(),
+            degree: setup.g1_powers.len() - 1,
+            crs_g1: setup.g1_powers.clone(),
+            crs_g2: setup.g2_powers.clone(),
+            lagrange_domain: None,
+            crs_lagrange: vec![],
+        })
     }
 
-    pub fn commit(&self, poly: &[E::ScalarField]) -> E::G1 {
-        let mut commitment = self.g1.mul(E::ScalarField::ZERO);
+    pub fn setup(&mut self, secret: E::ScalarField) {
+        let mut crs_g1 = Vec::with_capacity(self.degree+1);
+        let mut crs_g2 = Vec::with_capacity(self.degree+1);
         for i in 0..self.degree+1 {
-            commitment += self.crs_g1[i] * poly[i];
+            crs_g1.push(self.g1.mul(secret.pow([i as u64])));
+            crs_g2.push(self.g2.mul(secret.pow([i as u64])));
         }
-        commitment
+
+        // store the CRS in affine form for multi-scalar multiplication
+        self.crs_g1 = E::G1::normalize_batch(&crs_g1);
+        self.crs_g2 = E::G2::normalize_batch(&crs_g2);
+        self.g2_tau = self.g2.mul(secret);
     }
 
-    pub fn open(&self, poly: &[E::ScalarField], point: E::ScalarField) -> E::G1 {
-        // evaluate the polynomial at point
-        let value = evaluate(poly, point);
+    // derive the Lagrange basis over the smallest radix-2 domain with at least size elements from crs_g1,
+    // i.e. [L_i(tau)]_1 = 1/n sum_j w^(-ij) [tau^j]_1 is the inverse FFT of the first n powers
+    pub fn setup_lagrange(&mut self, size: usize) -> Result<(), KzgError> {
+        self.check_degree(0)?;
+        let domain = EvaluationDomain::<E::ScalarField>::new(size)?;
+        self.check_degree(domain.size)?;
 
-        // initialize denominator
-        let denominator = [-point, E::ScalarField::ONE];
+        let powers: Vec<E::G1> = self.crs_g1[..domain.size].iter().map(|&point| point.into()).collect();
+        self.crs_lagrange = E::G1::normalize_batch(&domain.group_ifft(&powers)?);
+        self.lagrange_domain = Some(domain);
+        Ok(())
+    }
+
+    // a polynomial with fewer than degree + 1 coefficients is committed as if padded with zeros
+    pub fn commit(&self, poly: &[E::ScalarField]) -> Result<Commitment<E>, KzgError> {
+        // make sure that the polynomial fits in the CRS
+        self.check_degree(poly.len())?;
 
-        // initialize numerator
-        let first = poly[0] - value;
-        let rest = &poly[1..];
-        let temp: Vec<E::ScalarField> = std::iter::once(first).chain(rest.iter().cloned()).collect();
-        let numerator: &[E::ScalarField] = &temp;
+        Ok(Commitment(msm::<E::G1>(&self.crs_g1, poly)))
+    }
 
-        // get quotient by dividing numerator by denominator
-        let quotient = div(numerator, &denominator).unwrap();
+    pub fn open(&self, poly: &[E::ScalarField], point: E::ScalarField) -> Result<Proof<E>, KzgError> {
+        // make sure that the polynomial fits in the CRS
+        self.check_degree(poly.len())?;
 
         // calculate pi as proof (quotient multiplied by CRS)
-        let mut pi = self.g1.mul(E::ScalarField::ZERO);
-        for i in 0..quotient.len() {
-            pi += self.crs_g1[i] * quotient[i];
-        }
+        Ok(Proof(msm::<E::G1>(&self.crs_g1, &open_quotient(poly, point)?)))
+    }
 
-        // return pi
-        pi
+    // FFTs of the CRS that open_all needs for polynomials up to max_degree, computed once and reused
+    pub fn fk20_precomputation(&self, max_degree: usize) -> Result<Fk20Precomputation<E::G1>, KzgError> {
+        self.check_degree(max_degree + 1)?;
+        Fk20Precomputation::new(&self.crs_g1, max_degree, 1)
     }
 
-    pub fn multi_open(&self, poly: &[E::ScalarField], points: &[E::ScalarField]) -> E::G1 {
-        // denominator is a polynomial where all its root are points to be evaluated (zero poly)
-        let mut zero_poly = vec![-points[0], E::ScalarField::ONE];
-        for i in 1..points.len() {
-            zero_poly = mul(&zero_poly, &[-points[i], E::ScalarField::ONE]);
+    // open the polynomial at every point of the domain, i.e. the i-th proof is the same as open(poly, w^i),
+    // computed with FK20 in O(n log n) instead of n separate openings, given the precomputation of this CRS
+    pub fn open_all(
+        &self,
+        poly: &[E::ScalarField],
+        domain: &EvaluationDomain<E::ScalarField>,
+        precomputation: &Fk20Precomputation<E::G1>
+    ) -> Result<Vec<Proof<E>>, KzgError> {
+        // make sure that the polynomial fits in the CRS
+        self.check_degree(poly.len())?;
+        if precomputation.coset_size != 1 {
+            return Err(KzgError::LengthMismatch { expected: 1, actual: precomputation.coset_size });
         }
 
-        // perform Lagrange interpolation on points
-        let mut values = vec![];
-        for i in 0..points.len() {
-            values.push(evaluate(poly, points[i]));
+        Ok(precomputation.proofs(poly, domain)?.into_iter().map(Proof).collect())
+    }
+
+    // open several polynomials at the same point with one proof, which is the opening of their linear
+    // combination with powers of the challenge
+    pub fn batch_open(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point: E::ScalarField,
+        challenge: E::ScalarField
+    ) -> Result<Proof<E>, KzgError> {
+        if polys.is_empty() {
+            return Err(KzgError::EmptyInput);
         }
-        let mut lagrange_poly = interpolate(points, &values).unwrap();
-        lagrange_poly.resize(poly.len(), E::ScalarField::ZERO); // pad with zeros
 
-        // numerator is the difference between the polynomial and the Lagrange interpolation
-        let mut numerator = Vec::with_capacity(poly.len());
-        for (coeff1, coeff2) in poly.iter().zip(lagrange_poly.as_slice()) {
-            numerator.push(*coeff1 - coeff2);
+        let mut combined_poly = DensePolynomial::zero();
+        let mut challenge_power = E::ScalarField::ONE;
+        for poly in polys {
+            // make sure that every polynomial fits in the CRS
+            self.check_degree(poly.as_ref().len())?;
+            combined_poly += &(DensePolynomial::from_coefficients_slice(poly.as_ref()) * challenge_power);
+            challenge_power *= challenge;
         }
 
-        // get quotient by dividing numerator by denominator
-        let quotient = div(&numerator, &zero_poly).unwrap();
+        self.open(&combined_poly, point)
+    }
 
-        // calculate pi as proof (quotient multiplied by CRS)
-        let mut pi = self.g1.mul(E::ScalarField::ZERO);
-        for i in 0..quotient.len() {
-            pi += self.crs_g1[i] * quotient[i];
+    // commit to the polynomial with the given evaluations over the Lagrange domain (padded with zeros)
+    pub fn commit_lagrange(&self, evals: &[E::ScalarField]) -> Result<Commitment<E>, KzgError> {
+        self.check_lagrange(evals.len())?;
+
+        Ok(Commitment(msm::<E::G1>(&self.crs_lagrange, evals)))
+    }
+
+    // open the polynomial with the given evaluations over the Lagrange domain at point, computing the
+    // quotient q(X) = (p(X) - p(z)) / (X - z) directly in evaluation form: q(w^i) = (p_i - p(z)) / (w^i - z),
+    // except at z = w^m inside the domain where q(w^m) = p'(w^m) = -sum_{i != m} q(w^i) w^(i-m)
+    pub fn open_lagrange(&self, evals: &[E::ScalarField], point: E::ScalarField) -> Result<Proof<E>, KzgError> {
+        let domain = self.check_lagrange(evals.len())?;
+        let mut evals = evals.to_vec();
+        evals.resize(domain.size, E::ScalarField::ZERO);
+        let elements = domain.elements();
+        let index = elements.iter().position(|&element| element == point);
+
+        // denominators w^i - z, with the one of the point itself left out if it is in the domain
+        let mut denominators: Vec<E::ScalarField> = elements.iter().map(|&element| element - point).collect();
+        if let Some(m) = index {
+            denominators[m] = E::ScalarField::ONE;
         }
+        batch_inversion(&mut denominators);
 
-        // return pi
-        pi
+        let value = match index {
+            Some(m) => evals[m],
+            None => domain.evaluate_lagrange(&evals, point)?,
+        };
+        let mut quotient: Vec<E::ScalarField> = evals.iter().zip(&denominators)
+            .map(|(&eval, &denominator_inv)| (eval - value) * denominator_inv)
+            .collect();
+        if let Some(m) = index {
+            quotient[m] = E::ScalarField::ZERO;
+            let sum: E::ScalarField = quotient.iter().zip(&elements).map(|(&q, &element)| q * element).sum();
+            quotient[m] = -sum * elements[(domain.size - m) % domain.size];
+        }
+
+        Ok(Proof(msm::<E::G1>(&self.crs_lagrange, &quotient)))
+    }
+
+    pub fn multi_open(&self, poly: &[E::ScalarField], points: &[E::ScalarField]) -> Result<Proof<E>, KzgError> {
+        // make sure that the polynomial fits in the CRS
+        self.check_degree(poly.len())?;
+
+        // calculate pi as proof (quotient multiplied by CRS)
+        Ok(Proof(msm::<E::G1>(&self.crs_g1, &multi_open_quotient(poly, points)?)))
     }
 
     pub fn verify(
         &self,
         point: E::ScalarField,
         value: E::ScalarField,
-        commitment: E::G1,
-        pi: E::G1
-    ) -> bool {
-        let lhs = E::pairing(pi, self.g2_tau - self.g2.mul(point));
-        let rhs = E::pairing(commitment - self.g1.mul(value), self.g2);
-        lhs == rhs
+        commitment: Commitment<E>,
+        pi: Proof<E>
+    ) -> Result<bool, KzgError> {
+        // make sure that g2_tau has been generated
+        self.check_degree(0)?;
+
+        Ok(check_opening::<E>((self.g1, self.g2, self.g2_tau), point, value, commitment, pi))
+    }
+
+    // open each polynomial f_i at its own set of points S_i with a single proof, where the challenge gamma
+    // combines the polynomials and the evaluation point z of the second round is derived from gamma and W
+    pub fn multi_poly_open(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_sets: &[impl AsRef<[E::ScalarField]>],
+        challenge: E::ScalarField
+    ) -> Result<MultiPolyProof<E>, KzgError> {
+        // make sure that every polynomial fits in the CRS and has its own set of distinct points
+        if polys.is_empty() {
+            return Err(KzgError::EmptyInput);
+        }
+        if point_sets.len() != polys.len() {
+            return Err(KzgError::LengthMismatch { expected: polys.len(), actual: point_sets.len() });
+        }
+        for poly in polys {
+            self.check_degree(poly.as_ref().len())?;
+        }
+        let all_points = union_of_points(point_sets)?;
+
+        let polys: Vec<_> = polys.iter().map(|poly| DensePolynomial::from_coefficients_slice(poly.as_ref())).collect();
+        let zero_poly = DensePolynomial::from_roots(&all_points);
+
+        // remainders r_i interpolating f_i on S_i
+        let mut remainders = Vec::with_capacity(polys.len());
+        for (poly, points) in polys.iter().zip(point_sets) {
+            let values: Vec<E::ScalarField> = points.as_ref().iter().map(|&point| poly.evaluate(point)).collect();
+            remainders.push(DensePolynomial::interpolate(points.as_ref(), &values)?);
+        }
+
+        // h = sum gamma^i (f_i - r_i) Z_{T \ S_i} / Z_T, where every term vanishes on all of T
+        let mut numerator = DensePolynomial::zero();
+        let mut challenge_power = E::ScalarField::ONE;
+        for ((poly, remainder), points) in polys.iter().zip(&remainders).zip(point_sets) {
+            let complement = DensePolynomial::from_roots(&difference_of_points(&all_points, points.as_ref()));
+            numerator += &(&(poly - remainder) * &complement * challenge_power);
+            challenge_power *= challenge;
+        }
+        let (h, _) = numerator.divide_with_remainder(&zero_poly)?;
+        let w = msm::<E::G1>(&self.crs_g1, &h);
+
+        // L = sum gamma^i Z_{T \ S_i}(z) (f_i - r_i(z)) - Z_T(z) h vanishes at z
+        let z = multi_poly_challenge::<E>(challenge, w);
+        let mut linearization = h * -zero_poly.evaluate(z);
+        let mut challenge_power = E::ScalarField::ONE;
+        for ((poly, remainder), points) in polys.iter().zip(&remainders).zip(point_sets) {
+            let complement_at_z = DensePolynomial::from_roots(&difference_of_points(&all_points, points.as_ref())).evaluate(z);
+            let weight = challenge_power * complement_at_z;
+            linearization += &(poly * weight - DensePolynomial::constant(weight * remainder.evaluate(z)));
+            challenge_power *= challenge;
+        }
+        let w_prime = self.open(&linearization, z)?.0;
+
+        Ok(MultiPolyProof { w, w_prime })
+    }
+
+    // verify a proof of batch_open against the commitments of the polynomials and their claimed values,
+    // combining both with powers of the challenge
+    pub fn verify_batch_open(
+        &self,
+        commitments: &[Commitment<E>],
+        point: E::ScalarField,
+        values: &[E::ScalarField],
+        pi: Proof<E>,
+        challenge: E::ScalarField
+    ) -> Result<bool, KzgError> {
+        if commitments.is_empty() {
+            return Err(KzgError::EmptyInput);
+        }
+        if values.len() != commitments.len() {
+            return Err(KzgError::LengthMismatch { expected: commitments.len(), actual: values.len() });
+        }
+
+        let challenge_powers: Vec<E::ScalarField> = (0..commitments.len()).map(|i| challenge.pow([i as u64])).collect();
+        let combined_commitment = Commitment::linear_combination(commitments, &challenge_powers)?;
+        let combined_value: E::ScalarField = challenge_powers.iter().zip(values).map(|(power, value)| *power * value).sum();
+
+        self.verify(point, combined_value, combined_commitment, pi)
+    }
+
+    // verify many single point openings at once, combining them with powers
+    // of a challenge r derived from all openings, i.e. with two pairings in total
+    // e(sum r^i pi_i, [tau]_2) = e(sum r^i (commitment_i - [value_i]_1 + point_i pi_i), [1]_2)
+    pub fn verify_batch(&self, openings: &[Opening<E>]) -> Result<bool, KzgError> {
+        // make sure that g2_tau has been generated
+        self.check_degree(0)?;
+
+        Ok(check_batch::<E>((self.g1, self.g2, self.g2_tau), openings))
     }
 
     pub fn verify_multi(
         &self,
         points: &[E::ScalarField],
         values: &[E::ScalarField],
-        commitment: E::G1,
-        pi: E::G1
-    ) -> bool {
-        // compute the zero polynomial
-        let mut zero_poly = vec![-points[0], E::ScalarField::ONE];
-        for i in 1..points.len() {
-            zero_poly = mul(&zero_poly, &[-points[i], E::ScalarField::ONE]);
+        commitment: Commitment<E>,
+        pi: Proof<E>
+    ) -> Result<bool, KzgError> {
+        // make sure that setup has been run
+        self.check_degree(0)?;
+
+        check_multi_opening::<E>(self.g2, &self.crs_g1, &self.crs_g2, points, values, commitment, pi)
+    }
+
+    // verify a proof of multi_poly_open against the commitments of the polynomials and their claimed values
+    // at their sets of points, i.e. that the commitment to L, computed from the commitments and W, opens to
+    // zero at z: F = sum gamma^i Z_{T \ S_i}(z) (C_i - [r_i(z)]_1) - Z_T(z) W and e(F + z W', [1]_2) = e(W', [tau]_2)
+    pub fn verify_multi_poly(
+        &self,
+        commitments: &[Commitment<E>],
+        point_sets: &[impl AsRef<[E::ScalarField]>],
+        value_sets: &[impl AsRef<[E::ScalarField]>],
+        proof: &MultiPolyProof<E>,
+        challenge: E::ScalarField
+    ) -> Result<bool, KzgError> {
+        if commitments.is_empty() {
+            return Err(KzgError::EmptyInput);
+        }
+        if point_sets.len() != commitments.len() {
+            return Err(KzgError::LengthMismatch { expected: commitments.len(), actual: point_sets.len() });
+        }
+        if value_sets.len() != commitments.len() {
+            return Err(KzgError::LengthMismatch { expected: commitments.len(), actual: value_sets.len() });
+        }
+        let all_points = union_of_points(point_sets)?;
+
+        let z = multi_poly_challenge::<E>(challenge, proof.w);
+        let mut lincomb = proof.w.mul(-DensePolynomial::from_roots(&all_points).evaluate(z));
+        let mut challenge_power = E::ScalarField::ONE;
+        for ((commitment, points), values) in commitments.iter().zip(point_sets).zip(value_sets) {
+            let (points, values) = (points.as_ref(), values.as_ref());
+            if values.len() != points.len() {
+                return Err(KzgError::LengthMismatch { expected: points.len(), actual: values.len() });
+            }
+            let remainder_at_z = DensePolynomial::interpolate(points, values)?.evaluate(z);
+            let complement_at_z = DensePolynomial::from_roots(&difference_of_points(&all_points, points)).evaluate(z);
+            lincomb += (commitment.0 - self.g1.mul(remainder_at_z)).mul(challenge_power * complement_at_z);
+            challenge_power *= challenge;
+        }
+
+        self.verify(z, E::ScalarField::ZERO, Commitment(lincomb), Proof(proof.w_prime))
+    }
+
+    // prove that the committed polynomial has degree below bound (1 <= bound <= degree) with the commitment
+    // to the shifted polynomial X^(degree + 1 - bound) p(X), which only fits in the CRS if deg(p) < bound
+    pub fn prove_degree_bound(&self, poly: &[E::ScalarField], bound: usize) -> Result<Commitment<E>, KzgError> {
+        self.check_degree(poly.len())?;
+        let shift = self.degree_bound_shift(bound)?;
+        let poly = DensePolynomial::from_coefficients_slice(poly);
+        if poly.degree() >= bound {
+            return Err(KzgError::DegreeTooLarge { degree: poly.degree(), max_degree: bound - 1 });
+        }
+
+        let mut shifted_poly = vec![E::ScalarField::ZERO; shift];
+        shifted_poly.extend_from_slice(&poly);
+        Ok(Commitment(msm::<E::G1>(&self.crs_g1, &shifted_poly)))
+    }
+
+    // verify that the shifted commitment is the commitment multiplied by tau^(degree + 1 - bound), i.e.
+    // e(shifted_commitment, [1]_2) = e(commitment, [tau^(degree + 1 - bound)]_2), which needs the G2 powers
+    // of the CRS up to the largest shift that is used
+    pub fn verify_degree_bound(
+        &self,
+        commitment: Commitment<E>,
+        bound: usize,
+        shifted_commitment: Commitment<E>
+    ) -> Result<bool, KzgError> {
+        self.check_degree(0)?;
+        let shift = self.degree_bound_shift(bound)?;
+        let shift_g2 = self.crs_g2.get(shift).copied()
+            .ok_or(KzgError::DegreeTooLarge { degree: shift, max_degree: self.crs_g2.len().saturating_sub(1) })?;
+
+        let lhs = E::pairing(shifted_commitment.0, self.g2);
+        let rhs = E::pairing(commitment.0, shift_g2);
+        Ok(lhs == rhs)
+    }
+
+    // power of tau by which a polynomial of degree below bound is shifted to the top of the CRS
+    fn degree_bound_shift(&self, bound: usize) -> Result<usize, KzgError> {
+        if bound == 0 || bound > self.degree {
+            return Err(KzgError::DegreeTooLarge { degree: bound, max_degree: self.degree });
+        }
+        Ok(self.degree + 1 - bound)
+    }
+
+    // keys for polynomials up to max_degree: the committer key only holds the G1 powers they need, and the
+    // verifier key only the generators and [tau]_2, so it can verify single point openings
+    pub fn trim(&self, max_degree: usize) -> Result<(CommitterKey<E>, VerifierKey<E>), KzgError> {
+        self.check_degree(0)?;
+        if max_degree > self.degree {
+            return Err(KzgError::DegreeTooLarge { degree: max_degree, max_degree: self.degree });
         }
 
-        // compute commitment of zero polynomial in regards to crs_g2
-        let mut zero_commitment = self.g2.mul(E::ScalarField::ZERO);
-        for i in 0..zero_poly.len() {
-            zero_commitment += self.crs_g2[i] * zero_poly[i];
+        let committer_key = CommitterKey {
+            degree: max_degree,
+            crs_g1: self.crs_g1[..max_degree + 1].to_vec()
+        };
+        Ok((committer_key, self.verifier_key(0)?))
+    }
+
+    // verifier key that also holds the powers to verify multi point openings at up to max_points points
+    pub fn verifier_key(&self, max_points: usize) -> Result<VerifierKey<E>, KzgError> {
+        self.check_degree(0)?;
+        if max_points >= self.crs_g2.len() {
+            return Err(KzgError::DegreeTooLarge { degree: max_points, max_degree: self.crs_g2.len() - 1 });
+        }
+
+        Ok(VerifierKey {
+            g1: self.g1,
+            g2: self.g2,
+            g2_tau: self.g2_tau,
+            crs_g1: if max_points > 0 { self.crs_g1[..max_points].to_vec() } else { vec![] },
+            crs_g2: if max_points > 0 { self.crs_g2[..max_points + 1].to_vec() } else { vec![] }
+        })
+    }
+
+    // check that the Lagrange basis has been derived and num_evals evaluations fit in its domain
+    fn check_lagrange(&self, num_evals: usize) -> Result<&EvaluationDomain<E::ScalarField>, KzgError> {
+        let domain = self.lagrange_domain.as_ref().ok_or(KzgError::SetupNotRun)?;
+        if num_evals > domain.size {
+            return Err(KzgError::LengthMismatch { expected: domain.size, actual: num_evals });
         }
+        Ok(domain)
+    }
+
+    // check that setup has been run and a polynomial with num_coeffs coefficients fits in the CRS
+    fn check_degree(&self, num_coeffs: usize) -> Result<(), KzgError> {
+        if self.crs_g1.is_empty() {
+            return Err(KzgError::SetupNotRun);
+        }
+        if num_coeffs > self.crs_g1.len() {
+            return Err(KzgError::DegreeTooLarge { degree: num_coeffs - 1, max_degree: self.degree });
+        }
+        Ok(())
+    }
+}
 
-        // compute lagrange polynomial
-        let lagrange_poly = interpolate(points, &values).unwrap();
+impl <E: Pairing> Valid for KZG<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        (self.g1, self.g2, self.g2_tau).check()?;
+        self.crs_g1.check()?;
+        self.crs_g2.check()?;
+        self.crs_lagrange.check()?;
 
-        // compute commitment of lagrange polynomial in regards to crs_g1
-        let mut lagrange_commitment = self.g1.mul(E::ScalarField::ZERO);
-        for i in 0..lagrange_poly.len() {
-            lagrange_commitment += self.crs_g1[i] * lagrange_poly[i];
+        // before setup there are no powers at all, afterwards degree + 1 powers in G1 starting with the
+        // generators, and at least [1]_2 and [tau]_2 in G2
+        if self.crs_g1.is_empty() {
+            if !self.crs_g2.is_empty() || self.lagrange_domain.is_some() || !self.crs_lagrange.is_empty() {
+                return Err(SerializationError::InvalidData);
+            }
+            return Ok(());
         }
+        if self.degree.checked_add(1) != Some(self.crs_g1.len()) || self.crs_g2.len() < 2 {
+            return Err(SerializationError::InvalidData);
+        }
+        if self.crs_g1[0] != self.g1.into_affine() || self.crs_g2[0] != self.g2.into_affine() || self.crs_g2[1] != self.g2_tau.into_affine() {
+            return Err(SerializationError::InvalidData);
+        }
+
+        // the Lagrange basis has one point per element of its domain, which fits in the CRS
+        let lagrange_size = self.lagrange_domain.map_or(0, |domain| domain.size);
+        if self.crs_lagrange.len() != lagrange_size || lagrange_size > self.crs_g1.len() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl <E: Pairing> CanonicalDeserialize for KZG<E> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let kzg = Self {
+            g1: E::G1::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            g2: E::G2::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            g2_tau: E::G2::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            degree: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            crs_g1: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            crs_g2: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            lagrange_domain: Option::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            crs_lagrange: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+        };
+        if validate == Validate::Yes {
+            kzg.check()?;
+        }
+        Ok(kzg)
+    }
+}
+
+// deserialization checks that the key holds degree + 1 powers
+impl <E: Pairing> Valid for CommitterKey<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.crs_g1.check()?;
+        if self.degree.checked_add(1) != Some(self.crs_g1.len()) {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl <E: Pairing> CanonicalDeserialize for CommitterKey<E> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let key = Self {
+            degree: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            crs_g1: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?
+        };
+        if validate == Validate::Yes {
+            key.check()?;
+        }
+        Ok(key)
+    }
+}
+
+impl <E: Pairing> CommitterKey<E> {
+    pub fn commit(&self, poly: &[E::ScalarField]) -> Result<Commitment<E>, KzgError> {
+        self.check_degree(poly.len())?;
+        Ok(Commitment(msm::<E::G1>(&self.crs_g1, poly)))
+    }
+
+    pub fn open(&self, poly: &[E::ScalarField], point: E::ScalarField) -> Result<Proof<E>, KzgError> {
+        self.check_degree(poly.len())?;
+        Ok(Proof(msm::<E::G1>(&self.crs_g1, &open_quotient(poly, point)?)))
+    }
+
+    pub fn multi_open(&self, poly: &[E::ScalarField], points: &[E::ScalarField]) -> Result<Proof<E>, KzgError> {
+        self.check_degree(poly.len())?;
+        Ok(Proof(msm::<E::G1>(&self.crs_g1, &multi_open_quotient(poly, points)?)))
+    }
+
+    // check that a polynomial with num_coeffs coefficients fits in the key
+    fn check_degree(&self, num_coeffs: usize) -> Result<(), KzgError> {
+        if num_coeffs > self.crs_g1.len() {
+            return Err(KzgError::DegreeTooLarge { degree: num_coeffs - 1, max_degree: self.degree });
+        }
+        Ok(())
+    }
+}
+
+impl <E: Pairing> VerifierKey<E> {
+    pub fn verify(
+        &self,
+        point: E::ScalarField,
+        value: E::ScalarField,
+        commitment: Commitment<E>,
+        pi: Proof<E>
+    ) -> Result<bool, KzgError> {
+        Ok(check_opening::<E>((self.g1, self.g2, self.g2_tau), point, value, commitment, pi))
+    }
+
+    pub fn verify_batch(&self, openings: &[Opening<E>]) -> Result<bool, KzgError> {
+        Ok(check_batch::<E>((self.g1, self.g2, self.g2_tau), openings))
+    }
 
-        let lhs = E::pairing(pi, zero_commitment);
-        let rhs = E::pairing(commitment - lagrange_commitment, self.g2);
-        lhs == rhs
+    pub fn verify_multi(
+        &self,
+        points: &[E::ScalarField],
+        values: &[E::ScalarField],
+        commitment: Commitment<E>,
+        pi: Proof<E>
+    ) -> Result<bool, KzgError> {
+        check_multi_opening::<E>(self.g2, &self.crs_g1, &self.crs_g2, points, values, commitment, pi)
     }
-}
\ No newline at end of file
+}
+
+// quotient (p(X) - p(z)) / (X - z) of a single point opening
+fn open_quotient<F: Field>(poly: &[F], point: F) -> Result<DensePolynomial<F>, KzgError> {
+    // evaluate the polynomial at point
+    let poly = DensePolynomial::from_coefficients_slice(poly);
+    let value = poly.evaluate(point);
+
+    // initialize numerator and denominator
+    let numerator = poly - DensePolynomial::constant(value);
+    let denominator = DensePolynomial::from_roots(&[point]);
+
+    // get quotient by dividing numerator by denominator, the remainder is zero
+    Ok(numerator.divide_with_remainder(&denominator)?.0)
+}
+
+// quotient of a multi point opening by the zero polynomial of the points
+fn multi_open_quotient<F: FftField>(poly: &[F], points: &[F]) -> Result<DensePolynomial<F>, KzgError> {
+    // make sure that there is at least one point
+    if points.is_empty() {
+        return Err(KzgError::EmptyInput);
+    }
+    if points.iter().collect::<HashSet<_>>().len() != points.len() {
+        return Err(KzgError::DuplicatePoints);
+    }
+
+    // denominator is a polynomial where all its root are points to be evaluated (zero poly)
+    let zero_poly = DensePolynomial::from_roots(points);
+
+    // the remainder of dividing the polynomial by the zero polynomial is its Lagrange interpolation on
+    // the points, so the quotient is the same as the quotient of their difference by the zero polynomial
+    Ok(DensePolynomial::from_coefficients_slice(poly).divide_with_remainder(&zero_poly)?.0)
+}
+
+// e(pi, [tau - point]_2) = e(commitment - [value]_1, [1]_2) with the generators g1, g2 and [tau]_2
+fn check_opening<E: Pairing>(
+    (g1, g2, g2_tau): (E::G1, E::G2, E::G2),
+    point: E::ScalarField,
+    value: E::ScalarField,
+    commitment: Commitment<E>,
+    pi: Proof<E>
+) -> bool {
+    let lhs = E::pairing(pi.0, g2_tau - g2.mul(point));
+    let rhs = E::pairing(commitment.0 - g1.mul(value), g2);
+    lhs == rhs
+}
+
+// batch check of single point openings, combining them with powers of a challenge r derived from all
+// openings: e(sum r^i pi_i, [tau]_2) = e(sum r^i (commitment_i - [value_i]_1 + point_i pi_i), [1]_2)
+fn check_batch<E: Pairing>((g1, g2, g2_tau): (E::G1, E::G2, E::G2), openings: &[Opening<E>]) -> bool {
+    if openings.is_empty() {
+        return true;
+    }
+
+    // random challenge over all openings
+    let mut hasher = Sha256::new();
+    for opening in openings {
+        let mut bytes = Vec::new();
+        opening.serialize_compressed(&mut bytes).unwrap();
+        hasher.update(&bytes);
+    }
+    let r = E::ScalarField::from_le_bytes_mod_order(&hasher.finalize());
+    let r_powers: Vec<E::ScalarField> = (0..openings.len()).map(|i| r.pow([i as u64])).collect();
+
+    let commitments: Vec<E::G1> = openings.iter().map(|opening| opening.0.0).collect();
+    let pis: Vec<E::G1> = openings.iter().map(|opening| opening.3.0).collect();
+    let commitments = E::G1::normalize_batch(&commitments);
+    let pis = E::G1::normalize_batch(&pis);
+    let r_points: Vec<E::ScalarField> = r_powers.iter().zip(openings).map(|(r_power, opening)| *r_power * opening.1).collect();
+    let r_values: E::ScalarField = r_powers.iter().zip(openings).map(|(r_power, opening)| *r_power * opening.2).sum();
+
+    let lhs = msm::<E::G1>(&pis, &r_powers);
+    let rhs = msm::<E::G1>(&commitments, &r_powers) + msm::<E::G1>(&pis, &r_points) - g1.mul(r_values);
+    let check = E::multi_pairing([lhs.into_affine(), (-rhs).into_affine()], [g2_tau.into_affine(), g2.into_affine()]);
+    check.is_zero()
+}
+
+// check a multi point opening with the first powers of the CRS: e(pi, [Z(tau)]_2) = e(commitment - [I(tau)]_1, [1]_2)
+// for the zero polynomial Z of the points and the interpolation I of the values
+pub(crate) fn check_multi_opening<E: Pairing>(
+    g2: E::G2,
+    crs_g1: &[E::G1Affine],
+    crs_g2: &[E::G2Affine],
+    points: &[E::ScalarField],
+    values: &[E::ScalarField],
+    commitment: Commitment<E>,
+    pi: Proof<E>
+) -> Result<bool, KzgError> {
+    // make sure that there is at least one point and the zero and lagrange polynomials fit in the CRS
+    if points.is_empty() {
+        return Err(KzgError::EmptyInput);
+    }
+    if points.len() >= crs_g2.len() || points.len() > crs_g1.len() {
+        let max_degree = std::cmp::min(crs_g2.len().saturating_sub(1), crs_g1.len());
+        return Err(KzgError::DegreeTooLarge { degree: points.len(), max_degree });
+    }
+
+    // compute the zero polynomial
+    let zero_poly = DensePolynomial::from_roots(points);
+
+    // compute commitment of zero polynomial in regards to crs_g2
+    let zero_commitment = msm::<E::G2>(crs_g2, &zero_poly);
+
+    // compute lagrange polynomial
+    let lagrange_poly = DensePolynomial::interpolate(points, values)?;
+
+    // compute commitment of lagrange polynomial in regards to crs_g1
+    let lagrange_commitment = msm::<E::G1>(crs_g1, &lagrange_poly);
+
+    let lhs = E::pairing(pi.0, zero_commitment);
+    let rhs = E::pairing(commitment.0 - lagrange_commitment, g2);
+    Ok(lhs == rhs)
+}
+
+// all distinct points of the point sets, each of which must be non-empty and distinct
+fn union_of_points<F: FftField>(point_sets: &[impl AsRef<[F]>]) -> Result<Vec<F>, KzgError> {
+    let mut all_points = vec![];
+    let mut seen = HashSet::new();
+    for points in point_sets {
+        let points = points.as_ref();
+        if points.is_empty() {
+            return Err(KzgError::EmptyInput);
+        }
+        if points.iter().collect::<HashSet<_>>().len() != points.len() {
+            return Err(KzgError::DuplicatePoints);
+        }
+        all_points.extend(points.iter().filter(|&&point| seen.insert(point)));
+    }
+    Ok(all_points)
+}
+
+// points of all_points that are not in points
+fn difference_of_points<F: FftField>(all_points: &[F], points: &[F]) -> Vec<F> {
+    all_points.iter().filter(|point| !points.contains(point)).copied().collect()
+}
+
+// evaluation point of the second round of a multi-polynomial opening, derived from gamma and W
+fn multi_poly_challenge<E: Pairing>(challenge: E::ScalarField, w: E::G1) -> E::ScalarField {
+    let mut bytes = Vec::new();
+    (challenge, w).serialize_compressed(&mut bytes).unwrap();
+    E::ScalarField::from_le_bytes_mod_order(&Sha256::digest(&bytes))
+}
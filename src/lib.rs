@@ -0,0 +1,24 @@
+pub mod kzg;
+pub mod asvc;
+pub mod utils;
+pub mod error;
+pub mod encoding;
+pub mod trusted_setup;
+pub mod ceremony;
+pub mod eip4844;
+pub mod eip7594;
+pub mod hiding;
+pub mod pcs;
+pub mod commitment;
+pub mod polynomial;
+
+pub use kzg::{KZG, CommitterKey, VerifierKey, MultiPolyProof};
+pub use asvc::{ASVC, CRS, ProvingKey, VerificationKey, VectorVerifierKey, UpdateKey};
+pub use error::KzgError;
+pub use trusted_setup::TrustedSetup;
+pub use ceremony::{Ceremony, ContributionProof};
+pub use eip4844::Eip4844;
+pub use hiding::{HidingKZG, HidingProof};
+pub use pcs::PolynomialCommitment;
+pub use commitment::{Commitment, Proof};
+pub use polynomial::DensePolynomial;
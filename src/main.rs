@@ -1,11 +1,13 @@
-pub mod kzg;
-pub mod asvc;
-pub mod utils;
-use kzg::KZG;
-use asvc::ASVC;
-use utils::evaluate;
+use kzg_rust::{KZG, CommitterKey, ASVC, VectorVerifierKey, KzgError, TrustedSetup, Ceremony, Eip4844, HidingKZG, PolynomialCommitment, Commitment, Proof, DensePolynomial};
+use kzg_rust::eip4844::{bls_field_to_bytes, bytes_to_kzg_commitment, BYTES_PER_BLOB, FIELD_ELEMENTS_PER_BLOB};
+use kzg_rust::eip7594::{CELLS_PER_EXT_BLOB, FIELD_ELEMENTS_PER_CELL};
+use kzg_rust::utils::{get_omega, EvaluationDomain};
+use kzg_rust::encoding::{from_hex, to_hex};
 use ark_std::UniformRand;
-use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1, G2Projective as G2};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine, G1Projective as G1, G2Projective as G2};
+use ark_ec::{AffineRepr, CurveGroup};
 use rand::seq::IteratorRandom;
 
 fn main() {
@@ -23,8 +25,8 @@ fn main() {
     kzg_instance.setup(secret);
 
     // generate a random polynomial and commit it
-    let poly = vec![Fr::rand(&mut rng); degree+1];
-    let commitment = kzg_instance.commit(&poly);
+    let poly = DensePolynomial::from_coefficients_vec(vec![Fr::rand(&mut rng); degree+1]);
+    let commitment = kzg_instance.commit(&poly).unwrap();
 
     // test single point evaluation
     test_single_evaluation(&kzg_instance, &poly, commitment);
@@ -32,90 +34,416 @@ fn main() {
     // test multi point evaluation
     test_multi_evaluation(&kzg_instance, &poly, commitment);
 
+    // test opening several polynomials at the same point
+    test_batch_opening(&kzg_instance);
+
+    // test opening several polynomials at their own points
+    test_multi_poly_opening(&kzg_instance);
+
+    // test opening at every point of a domain
+    test_open_all(&kzg_instance, &poly);
+
+    // test hiding commitments
+    test_hiding(&kzg_instance, &poly);
+
+    // test degree bounds of committed polynomials
+    test_degree_bound(&kzg_instance);
+
+    // test polynomials in evaluation form
+    test_lagrange(&kzg_instance);
+
+    // test trimmed committer and verifier keys
+    test_trim(&kzg_instance);
+
+    // test batch verification of many openings
+    test_batch_verification(&kzg_instance);
+
+    // test linear combinations of commitments and proofs
+    test_homomorphism(&kzg_instance);
+
+    // test FFT-based polynomial arithmetic
+    test_evaluation_domain();
+
+    // test polynomial arithmetic in coefficient form
+    test_dense_polynomial();
+
     // initialize asvc instance
     let asvc_instance = ASVC::<Bls12_381>::key_gen(
         G1::rand(&mut rng),
         G2::rand(&mut rng),
         degree,
         secret
-    );
+    ).unwrap();
 
     // generate a random vector and commit to it
-    let vector = vec![Fr::rand(&mut rng); degree];
-    let commitment = asvc_instance.vector_commit(&vector);
+    let vector: Vec<Fr> = (0..degree).map(|_| Fr::rand(&mut rng)).collect();
+    let commitment = asvc_instance.vector_commit(&vector).unwrap();
 
     // test vector evaluation
     test_vector_evaluation(&asvc_instance, &vector, commitment);
 
     // test proof aggregation
     test_proof_aggregation(&asvc_instance, &vector, commitment);
+
+    // test non-contiguous and unsorted positions
+    test_noncontiguous_positions(&asvc_instance, &vector, commitment);
+
+    // test every single position
+    test_single_positions(&asvc_instance, &vector, commitment);
+
+    // test commitment update
+    test_commitment_update(&asvc_instance, &vector, commitment);
+
+    // test proof update
+    test_proof_update(&asvc_instance, &vector, commitment);
+
+    // test vector length that is not a power of two
+    test_padded_vector(secret);
+
+    // test loading powers of tau from a ceremony file
+    test_trusted_setup(&kzg_instance);
+
+    // test a multi-party powers-of-tau ceremony
+    test_ceremony();
+
+    // test the same protocol over KZG and ASVC
+    test_polynomial_commitment(degree);
+
+    // test serialization of keys, commitments and proofs
+    test_serialization(&kzg_instance, &asvc_instance, &vector);
+
+    // test the EIP-4844 blob API and the EIP-7594 cells
+    let eip4844 = test_eip4844(secret);
+    test_eip7594(&eip4844);
+
+    // test invalid inputs are rejected with errors
+    test_invalid_inputs(&kzg_instance, &asvc_instance, &vector);
 }
 
 pub fn test_single_evaluation(
     kzg_instance: &KZG<Bls12_381>,
-    poly: &[Fr],
-    commitment: G1
+    poly: &DensePolynomial<Fr>,
+    commitment: Commitment<Bls12_381>
 ) {
     let mut rng = ark_std::test_rng();
 
     // generate a random point and open the polynomial at that point
     let point = Fr::rand(&mut rng);
-    let pi = kzg_instance.open(&poly, point);
+    let pi = kzg_instance.open(poly, point).unwrap();
 
     // verify the proof
-    let value = evaluate(&poly, point);
-    assert!(kzg_instance.verify(point, value, commitment, pi));
+    let value = poly.evaluate(point);
+    assert!(kzg_instance.verify(point, value, commitment, pi).unwrap());
 
     println!("Single point evaluation verified!");
 }
 
 pub fn test_multi_evaluation(
     kzg_instance: &KZG<Bls12_381>,
-    poly: &[Fr],
-    commitment: G1
+    poly: &DensePolynomial<Fr>,
+    commitment: Commitment<Bls12_381>
 ) {
     let mut rng = ark_std::test_rng();
 
     // generate three random points and open the polynomial at those points
     let points: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
-    let pi = kzg_instance.multi_open(&poly, &points);
+    let pi = kzg_instance.multi_open(poly, &points).unwrap();
 
     // evaluate the polynomial at those points
-    let mut values = vec![];
-    for i in 0..points.len() {
-        values.push(evaluate(poly, points[i]));
-    }
+    let values: Vec<Fr> = points.iter().map(|&point| poly.evaluate(point)).collect();
 
     // verify the proof
-    assert!(kzg_instance.verify_multi(&points, &values, commitment, pi));
+    assert!(kzg_instance.verify_multi(&points, &values, commitment, pi).unwrap());
 
     println!("Multi points evaluation verified!");
 }
 
+pub fn test_batch_opening(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+
+    // commit to polynomials of different degrees
+    let polys: Vec<DensePolynomial<Fr>> = (0..4).map(|degree| DensePolynomial::rand(degree, &mut rng)).collect();
+    let commitments: Vec<Commitment<Bls12_381>> = polys.iter().map(|poly| kzg_instance.commit(poly).unwrap()).collect();
+
+    // open them all at the same point with one proof
+    let point = Fr::rand(&mut rng);
+    let challenge = Fr::rand(&mut rng);
+    let pi = kzg_instance.batch_open(&polys, point, challenge).unwrap();
+    let mut values: Vec<Fr> = polys.iter().map(|poly| poly.evaluate(point)).collect();
+    assert!(kzg_instance.verify_batch_open(&commitments, point, &values, pi, challenge).unwrap());
+
+    // a different challenge or a wrong value fails
+    assert!(!kzg_instance.verify_batch_open(&commitments, point, &values, pi, challenge + Fr::ONE).unwrap());
+    values[2] += Fr::ONE;
+    assert!(!kzg_instance.verify_batch_open(&commitments, point, &values, pi, challenge).unwrap());
+    assert!(matches!(
+        kzg_instance.verify_batch_open(&commitments, point, &values[1..], pi, challenge),
+        Err(KzgError::LengthMismatch { .. })
+    ));
+
+    println!("Batch opening verified!");
+}
+
+pub fn test_multi_poly_opening(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+
+    // three polynomials opened at overlapping sets of points
+    let polys: Vec<DensePolynomial<Fr>> = (0..3).map(|_| DensePolynomial::rand(kzg_instance.degree, &mut rng)).collect();
+    let commitments: Vec<Commitment<Bls12_381>> = polys.iter().map(|poly| kzg_instance.commit(poly).unwrap()).collect();
+    let shared_point = Fr::rand(&mut rng);
+    let point_sets = vec![
+        vec![shared_point],
+        vec![Fr::rand(&mut rng), shared_point, Fr::rand(&mut rng)],
+        vec![Fr::rand(&mut rng), Fr::rand(&mut rng)],
+    ];
+    let mut value_sets: Vec<Vec<Fr>> = polys.iter().zip(&point_sets)
+        .map(|(poly, points)| points.iter().map(|&point| poly.evaluate(point)).collect())
+        .collect();
+
+    // the proof is two G1 elements regardless of the number of polynomials and points
+    let challenge = Fr::rand(&mut rng);
+    let proof = kzg_instance.multi_poly_open(&polys, &point_sets, challenge).unwrap();
+    assert!(kzg_instance.verify_multi_poly(&commitments, &point_sets, &value_sets, &proof, challenge).unwrap());
+
+    // a wrong value or a proof for another challenge fails
+    let other_proof = kzg_instance.multi_poly_open(&polys, &point_sets, challenge + Fr::ONE).unwrap();
+    assert!(!kzg_instance.verify_multi_poly(&commitments, &point_sets, &value_sets, &other_proof, challenge).unwrap());
+    value_sets[1][2] += Fr::ONE;
+    assert!(!kzg_instance.verify_multi_poly(&commitments, &point_sets, &value_sets, &proof, challenge).unwrap());
+    assert!(matches!(
+        kzg_instance.multi_poly_open(&polys, &point_sets[1..], challenge),
+        Err(KzgError::LengthMismatch { .. })
+    ));
+
+    println!("Multi-polynomial opening verified!");
+}
+
+pub fn test_open_all(kzg_instance: &KZG<Bls12_381>, poly: &DensePolynomial<Fr>) {
+    // all proofs over domains smaller and larger than the polynomial are the single point proofs, with
+    // the FFTs of the CRS computed once for both domains
+    let precomputation = kzg_instance.fk20_precomputation(poly.degree()).unwrap();
+    for size in [16, 32] {
+        let domain = EvaluationDomain::<Fr>::new(size).unwrap();
+        let proofs = kzg_instance.open_all(poly, &domain, &precomputation).unwrap();
+        assert_eq!(proofs.len(), size);
+        for (i, pi) in proofs.iter().enumerate() {
+            assert_eq!(*pi, kzg_instance.open(poly, domain.element(i)).unwrap());
+        }
+    }
+
+    // a domain smaller than the degree of the polynomial is rejected
+    let domain = EvaluationDomain::<Fr>::new(8).unwrap();
+    assert!(matches!(kzg_instance.open_all(poly, &domain, &precomputation), Err(KzgError::DegreeTooLarge { .. })));
+
+    // a precomputation for the whole CRS also opens lower degree polynomials, but not higher ones
+    let precomputation = kzg_instance.fk20_precomputation(kzg_instance.degree).unwrap();
+    let domain = EvaluationDomain::<Fr>::new(16).unwrap();
+    assert_eq!(kzg_instance.open_all(poly, &domain, &precomputation).unwrap()[3], kzg_instance.open(poly, domain.element(3)).unwrap());
+    let precomputation = kzg_instance.fk20_precomputation(poly.degree() - 1).unwrap();
+    assert!(matches!(kzg_instance.open_all(poly, &domain, &precomputation), Err(KzgError::DegreeTooLarge { .. })));
+
+    println!("All openings verified!");
+}
+
+pub fn test_hiding(kzg_instance: &KZG<Bls12_381>, poly: &DensePolynomial<Fr>) {
+    let mut rng = ark_std::test_rng();
+    let hiding_instance = HidingKZG::setup(kzg_instance.clone(), Fr::rand(&mut rng), 2).unwrap();
+
+    // committing twice gives different commitments that both open to the evaluations
+    let (commitment, blinding_poly) = hiding_instance.commit(poly, &mut rng).unwrap();
+    let (other_commitment, _) = hiding_instance.commit(poly, &mut rng).unwrap();
+    assert_ne!(commitment, other_commitment);
+    assert_ne!(commitment, kzg_instance.commit(poly).unwrap());
+
+    let point = Fr::rand(&mut rng);
+    let value = poly.evaluate(point);
+    let proof = hiding_instance.open(poly, &blinding_poly, point).unwrap();
+    assert!(hiding_instance.verify(point, value, commitment, &proof).unwrap());
+
+    // a wrong value, a wrong blinding value or another commitment fails
+    assert!(!hiding_instance.verify(point, value + Fr::ONE, commitment, &proof).unwrap());
+    assert!(!hiding_instance.verify(point, value, other_commitment, &proof).unwrap());
+    let mut wrong_proof = proof.clone();
+    wrong_proof.blinding_value += Fr::ONE;
+    assert!(!hiding_instance.verify(point, value, commitment, &wrong_proof).unwrap());
+
+    // the blinding polynomial has to fit in the CRS
+    assert!(matches!(
+        HidingKZG::setup(kzg_instance.clone(), Fr::rand(&mut rng), kzg_instance.degree + 1),
+        Err(KzgError::DegreeTooLarge { .. })
+    ));
+
+    println!("Hiding commitments verified!");
+}
+
+pub fn test_degree_bound(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+
+    // a polynomial of degree 4 is below the bounds 5 and 16, but not below 4
+    let poly = DensePolynomial::rand(4, &mut rng);
+    let commitment = kzg_instance.commit(&poly).unwrap();
+    for bound in [5, kzg_instance.degree] {
+        let shifted_commitment = kzg_instance.prove_degree_bound(&poly, bound).unwrap();
+        assert!(kzg_instance.verify_degree_bound(commitment, bound, shifted_commitment).unwrap());
+    }
+    assert!(matches!(kzg_instance.prove_degree_bound(&poly, 4), Err(KzgError::DegreeTooLarge { .. })));
+
+    // a shifted commitment for a larger bound does not verify a smaller one
+    let shifted_commitment = kzg_instance.prove_degree_bound(&poly, 6).unwrap();
+    assert!(!kzg_instance.verify_degree_bound(commitment, 5, shifted_commitment).unwrap());
+    assert!(matches!(
+        kzg_instance.verify_degree_bound(commitment, kzg_instance.degree + 1, shifted_commitment),
+        Err(KzgError::DegreeTooLarge { .. })
+    ));
+
+    println!("Degree bounds verified!");
+}
+
+pub fn test_lagrange(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+    let mut kzg_instance = kzg_instance.clone();
+    let evals: Vec<Fr> = (0..16).map(|_| Fr::rand(&mut rng)).collect();
+    assert!(matches!(kzg_instance.commit_lagrange(&evals), Err(KzgError::SetupNotRun)));
+    kzg_instance.setup_lagrange(16).unwrap();
+
+    // committing to the evaluations is the same as committing to the interpolated coefficients
+    let domain = kzg_instance.lagrange_domain.unwrap();
+    let poly = DensePolynomial::from_coefficients_vec(domain.ifft(&evals).unwrap());
+    let commitment = kzg_instance.commit_lagrange(&evals).unwrap();
+    assert_eq!(commitment, kzg_instance.commit(&poly).unwrap());
+
+    // opening outside and inside the domain gives the same proofs as opening the coefficients
+    for point in [Fr::rand(&mut rng), domain.element(0), domain.element(5)] {
+        let pi = kzg_instance.open_lagrange(&evals, point).unwrap();
+        assert_eq!(pi, kzg_instance.open(&poly, point).unwrap());
+        let value = domain.evaluate_lagrange(&evals, point).unwrap();
+        assert_eq!(value, poly.evaluate(point));
+        assert!(kzg_instance.verify(point, value, commitment, pi).unwrap());
+    }
+
+    // more evaluations than the domain, or a domain larger than the CRS, are rejected
+    assert!(matches!(kzg_instance.commit_lagrange(&[evals.clone(), evals].concat()), Err(KzgError::LengthMismatch { .. })));
+    assert!(matches!(kzg_instance.setup_lagrange(32), Err(KzgError::DegreeTooLarge { .. })));
+
+    println!("Lagrange form verified!");
+}
+
+pub fn test_trim(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+
+    // keys for polynomials of degree 8 commit and open like the full instance
+    let (committer_key, verifier_key) = kzg_instance.trim(8).unwrap();
+    assert_eq!(committer_key.crs_g1.len(), 9);
+    let poly = DensePolynomial::rand(8, &mut rng);
+    let commitment = committer_key.commit(&poly).unwrap();
+    assert_eq!(commitment, kzg_instance.commit(&poly).unwrap());
+    let point = Fr::rand(&mut rng);
+    let pi = committer_key.open(&poly, point).unwrap();
+    assert!(verifier_key.verify(point, poly.evaluate(point), commitment, pi).unwrap());
+    assert!(verifier_key.verify_batch(&[(commitment, point, poly.evaluate(point), pi)]).unwrap());
+    assert!(matches!(committer_key.commit(&[poly.coeffs(), poly.coeffs()].concat()), Err(KzgError::DegreeTooLarge { .. })));
+
+    // the verifier key is a few hundred bytes
+    let mut bytes = Vec::new();
+    verifier_key.serialize_compressed(&mut bytes).unwrap();
+    assert!(bytes.len() < 300);
+
+    // multi point openings need a verifier key with powers for enough points
+    let points: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+    let values: Vec<Fr> = points.iter().map(|&point| poly.evaluate(point)).collect();
+    let pi = committer_key.multi_open(&poly, &points).unwrap();
+    assert!(matches!(verifier_key.verify_multi(&points, &values, commitment, pi), Err(KzgError::DegreeTooLarge { .. })));
+    let verifier_key = kzg_instance.verifier_key(3).unwrap();
+    assert!(verifier_key.verify_multi(&points, &values, commitment, pi).unwrap());
+    assert!(!verifier_key.verify_multi(&points, &[values[0], values[1], values[0]], commitment, pi).unwrap());
+
+    assert!(matches!(kzg_instance.trim(kzg_instance.degree + 1), Err(KzgError::DegreeTooLarge { .. })));
+
+    println!("Trimmed keys verified!");
+}
+
+pub fn test_batch_verification(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+
+    // open random polynomials at random points
+    let mut openings = Vec::new();
+    for _ in 0..8 {
+        let poly = DensePolynomial::rand(kzg_instance.degree, &mut rng);
+        let point = Fr::rand(&mut rng);
+        let commitment = kzg_instance.commit(&poly).unwrap();
+        let pi = kzg_instance.open(&poly, point).unwrap();
+        openings.push((commitment, point, poly.evaluate(point), pi));
+    }
+
+    // all openings verify at once, and a single wrong value fails the whole batch
+    assert!(kzg_instance.verify_batch(&openings).unwrap());
+    assert!(kzg_instance.verify_batch(&[]).unwrap());
+    openings[5].2 += Fr::ONE;
+    assert!(!kzg_instance.verify_batch(&openings).unwrap());
+
+    println!("Batch verification verified!");
+}
+
+pub fn test_homomorphism(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+
+    // the commitment to a * p + b * q is a * C_p + b * C_q
+    let p = DensePolynomial::rand(kzg_instance.degree, &mut rng);
+    let q = DensePolynomial::rand(kzg_instance.degree - 1, &mut rng);
+    let (a, b) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+    let combined_poly = &p * a + &q * b;
+    let (commitment_p, commitment_q) = (kzg_instance.commit(&p).unwrap(), kzg_instance.commit(&q).unwrap());
+    let combined_commitment = commitment_p * a + commitment_q * b;
+    assert_eq!(combined_commitment, kzg_instance.commit(&combined_poly).unwrap());
+    assert_eq!(combined_commitment, Commitment::linear_combination(&[commitment_p, commitment_q], &[a, b]).unwrap());
+    assert_eq!(combined_commitment - commitment_q * b, commitment_p * a);
+
+    // so is the proof at the same point, which opens the combined commitment to the combined value
+    let point = Fr::rand(&mut rng);
+    let (pi_p, pi_q) = (kzg_instance.open(&p, point).unwrap(), kzg_instance.open(&q, point).unwrap());
+    let combined_pi: Proof<Bls12_381> = [pi_p * a, pi_q * b].into_iter().sum();
+    let combined_value = a * p.evaluate(point) + b * q.evaluate(point);
+    assert!(kzg_instance.verify(point, combined_value, combined_commitment, combined_pi).unwrap());
+    assert!(!kzg_instance.verify(point, combined_value, combined_commitment, -combined_pi).unwrap());
+    assert!(matches!(
+        Proof::linear_combination(&[pi_p, pi_q], &[a]),
+        Err(KzgError::LengthMismatch { .. })
+    ));
+
+    // commitments and proofs serialize as the G1 point they wrap
+    let mut bytes = Vec::new();
+    combined_commitment.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 48);
+    let restored = Commitment::<Bls12_381>::deserialize_compressed(bytes.as_slice()).unwrap();
+    assert_eq!(restored, combined_commitment);
+
+    println!("Homomorphic commitments verified!");
+}
+
 pub fn test_vector_evaluation(
     asvc_instance: &ASVC<Bls12_381>,
     vector: &[Fr],
-    commitment: G1
+    commitment: Commitment<Bls12_381>
 ) {
     // randomly select three items in the vectors and also record their indices
     let mut rng = ark_std::test_rng();
     let mut selected_indices = Vec::new();
     while selected_indices.len() < 3 {
-        let value = (0..=15).choose(&mut rng).unwrap();
+        let value = (0..vector.len()).choose(&mut rng).unwrap();
         if !selected_indices.contains(&value) {
             selected_indices.push(value);
         }
     }
 
     // prove positions for these three selected indices
-    let pi = asvc_instance.prove_position(&selected_indices, &vector);
+    let pi = asvc_instance.prove_position(&selected_indices, vector).unwrap();
 
     // verify the proof
     let mut subvector = vec![];
     for &index in &selected_indices {
         subvector.push(vector[index]);
     }
-    assert!(asvc_instance.verify_position(commitment, &selected_indices, &subvector, pi));
+    assert!(asvc_instance.verify_position(commitment, &selected_indices, &subvector, pi).unwrap());
 
     println!("Vector evaluation verified!");
 }
@@ -123,33 +451,719 @@ pub fn test_vector_evaluation(
 pub fn test_proof_aggregation (
     asvc_instance: &ASVC<Bls12_381>,
     vector: &[Fr],
-    commitment: G1
+    commitment: Commitment<Bls12_381>
 ) {
     // randomly select three items in the vectors and also record their indices
     let mut rng = ark_std::test_rng();
     let mut selected_indices = Vec::new();
     while selected_indices.len() < 3 {
-        let value = (0..=15).choose(&mut rng).unwrap();
+        let value = (0..vector.len()).choose(&mut rng).unwrap();
         if !selected_indices.contains(&value) {
             selected_indices.push(value);
         }
     }
 
     // prove positions individually for these three selected indices
-    let pi_0 = asvc_instance.prove_position(&[selected_indices[0]], &vector);
-    let pi_1 = asvc_instance.prove_position(&[selected_indices[1]], &vector);
-    let pi_2 = asvc_instance.prove_position(&[selected_indices[2]], &vector);
+    let pi_0 = asvc_instance.prove_position(&[selected_indices[0]], vector).unwrap();
+    let pi_1 = asvc_instance.prove_position(&[selected_indices[1]], vector).unwrap();
+    let pi_2 = asvc_instance.prove_position(&[selected_indices[2]], vector).unwrap();
     let proofs = vec![pi_0, pi_1, pi_2];
 
     // aggregate these proofs
-    let pi = asvc_instance.aggregate_proofs(&selected_indices, proofs);
+    let pi = asvc_instance.aggregate_proofs(&selected_indices, proofs).unwrap();
 
     // verify the proof
     let mut subvector = vec![];
     for &index in &selected_indices {
         subvector.push(vector[index]);
     }
-    assert!(asvc_instance.verify_position(commitment, &selected_indices, &subvector, pi));
+    assert!(asvc_instance.verify_position(commitment, &selected_indices, &subvector, pi).unwrap());
 
     println!("Proof aggregation verified!");
-}
\ No newline at end of file
+}
+
+pub fn test_commitment_update(
+    asvc_instance: &ASVC<Bls12_381>,
+    vector: &[Fr],
+    commitment: Commitment<Bls12_381>
+) {
+    // randomly select an item in the vector and change it by a random delta
+    let mut rng = ark_std::test_rng();
+    let index = (0..vector.len()).choose(&mut rng).unwrap();
+    let delta = Fr::rand(&mut rng);
+    let mut updated_vector = vector.to_vec();
+    updated_vector[index] += delta;
+
+    // updated commitment should match the commitment of the updated vector
+    let updated_commitment = asvc_instance.update_commitment(commitment, index, delta).unwrap();
+    assert_eq!(updated_commitment, asvc_instance.vector_commit(&updated_vector).unwrap());
+
+    println!("Commitment update verified!");
+}
+
+pub fn test_proof_update(
+    asvc_instance: &ASVC<Bls12_381>,
+    vector: &[Fr],
+    commitment: Commitment<Bls12_381>
+) {
+    // randomly select two distinct items in the vector
+    let mut rng = ark_std::test_rng();
+    let mut selected_indices = Vec::new();
+    while selected_indices.len() < 2 {
+        let value = (0..vector.len()).choose(&mut rng).unwrap();
+        if !selected_indices.contains(&value) {
+            selected_indices.push(value);
+        }
+    }
+    let (proof_index, changed_index) = (selected_indices[0], selected_indices[1]);
+    let mut pi = asvc_instance.prove_position(&[proof_index], vector).unwrap();
+    let mut commitment = commitment;
+    let mut updated_vector = vector.to_vec();
+
+    // change another position and then the proven position itself, updating commitment and proof
+    for index in [changed_index, proof_index] {
+        let delta = Fr::rand(&mut rng);
+        updated_vector[index] += delta;
+        commitment = asvc_instance.update_commitment(commitment, index, delta).unwrap();
+        pi = asvc_instance.update_proof(pi, proof_index, index, delta).unwrap();
+
+        // the updated proof should verify against the updated commitment and match a fresh proof
+        assert!(asvc_instance.verify_position(commitment, &[proof_index], &[updated_vector[proof_index]], pi).unwrap());
+        assert_eq!(pi, asvc_instance.prove_position(&[proof_index], &updated_vector).unwrap());
+    }
+
+    println!("Proof update verified!");
+}
+
+pub fn test_noncontiguous_positions(
+    asvc_instance: &ASVC<Bls12_381>,
+    vector: &[Fr],
+    commitment: Commitment<Bls12_381>
+) {
+    // unsorted, non-contiguous indices spread across the vector
+    let selected_indices = [vector.len() - 3, 2, vector.len() / 2 + 1, 0];
+    let subvector: Vec<Fr> = selected_indices.iter().map(|&index| vector[index]).collect();
+
+    // prove and verify the subvector directly
+    let pi = asvc_instance.prove_position(&selected_indices, vector).unwrap();
+    assert!(asvc_instance.verify_position(commitment, &selected_indices, &subvector, pi).unwrap());
+
+    // the same proof should not verify a tampered subvector
+    let mut tampered_subvector = subvector.clone();
+    tampered_subvector[1] += Fr::from(1u32);
+    assert!(!asvc_instance.verify_position(commitment, &selected_indices, &tampered_subvector, pi).unwrap());
+
+    // aggregating individual proofs should give the same subvector proof
+    let proofs: Vec<Proof<Bls12_381>> = selected_indices.iter()
+        .map(|&index| asvc_instance.prove_position(&[index], vector).unwrap())
+        .collect();
+    let aggregated_pi = asvc_instance.aggregate_proofs(&selected_indices, proofs).unwrap();
+    assert_eq!(aggregated_pi, pi);
+    assert!(asvc_instance.verify_position(commitment, &selected_indices, &subvector, aggregated_pi).unwrap());
+
+    // a verifier key for up to four positions only holds the first powers, and rejects larger subvectors
+    let verifier_key = asvc_instance.verifier_key(selected_indices.len()).unwrap();
+    assert_eq!((verifier_key.crs.g1.len(), verifier_key.crs.g2.len()), (4, 5));
+    assert!(verifier_key.verify_position(commitment, &selected_indices, &subvector, pi).unwrap());
+    assert!(!verifier_key.verify_position(commitment, &selected_indices, &tampered_subvector, pi).unwrap());
+    let verifier_key = asvc_instance.verifier_key(2).unwrap();
+    assert!(matches!(
+        verifier_key.verify_position(commitment, &selected_indices, &subvector, pi),
+        Err(KzgError::DegreeTooLarge { .. })
+    ));
+
+    println!("Non-contiguous positions verified!");
+}
+
+pub fn test_single_positions(
+    asvc_instance: &ASVC<Bls12_381>,
+    vector: &[Fr],
+    commitment: Commitment<Bls12_381>
+) {
+    // precompute the proofs of every position at once
+    let all_proofs = asvc_instance.prove_all_positions(vector, &asvc_instance.fk20_precomputation().unwrap()).unwrap();
+    assert_eq!(all_proofs.len(), vector.len());
+
+    for index in 0..vector.len() {
+        // prove and verify a single-element subvector
+        let pi = asvc_instance.prove_position(&[index], vector).unwrap();
+        assert_eq!(pi, all_proofs[index]);
+        assert!(asvc_instance.verify_position(commitment, &[index], &[vector[index]], pi).unwrap());
+
+        // the proof should not verify the value of a different position
+        let other = (index + 1) % vector.len();
+        assert!(!asvc_instance.verify_position(commitment, &[other], &[vector[index]], pi).unwrap());
+    }
+
+    println!("Single positions verified!");
+}
+
+pub fn test_invalid_inputs(
+    kzg_instance: &KZG<Bls12_381>,
+    asvc_instance: &ASVC<Bls12_381>,
+    vector: &[Fr]
+) {
+    let mut rng = ark_std::test_rng();
+
+    // polynomial with more coefficients than the CRS supports
+    let poly = DensePolynomial::rand(kzg_instance.degree + 1, &mut rng);
+    assert!(matches!(kzg_instance.commit(&poly), Err(KzgError::DegreeTooLarge { .. })));
+
+    // polynomial with fewer coefficients is committed as if padded with zeros
+    let mut padded_poly = poly[..3].to_vec();
+    padded_poly.resize(kzg_instance.degree + 1, Fr::from(0u32));
+    assert_eq!(kzg_instance.commit(&poly[..3]).unwrap(), kzg_instance.commit(&padded_poly).unwrap());
+
+    // KZG instance without setup
+    let kzg_without_setup = KZG::<Bls12_381>::new(G1::rand(&mut rng), G2::rand(&mut rng), 4);
+    assert_eq!(kzg_without_setup.commit(&poly[..2]), Err(KzgError::SetupNotRun));
+
+    // interpolation over duplicate points
+    let point = Fr::rand(&mut rng);
+    assert_eq!(DensePolynomial::interpolate(&[point, point], &[Fr::from(1u32), Fr::from(2u32)]), Err(KzgError::DuplicatePoints));
+
+    // keys for a vector without positions
+    assert!(matches!(ASVC::<Bls12_381>::key_gen(G1::rand(&mut rng), G2::rand(&mut rng), 0, Fr::rand(&mut rng)), Err(KzgError::EmptyInput)));
+
+    // vector with the wrong length and positions outside the vector
+    assert!(matches!(asvc_instance.vector_commit(&vector[1..]), Err(KzgError::LengthMismatch { .. })));
+    assert!(matches!(
+        asvc_instance.prove_position(&[vector.len()], vector),
+        Err(KzgError::IndexOutOfRange { .. })
+    ));
+    assert_eq!(asvc_instance.prove_position(&[1, 1], vector), Err(KzgError::DuplicatePoints));
+
+    println!("Invalid inputs rejected!");
+}
+
+pub fn test_evaluation_domain() {
+    let mut rng = ark_std::test_rng();
+    let domain = EvaluationDomain::<Fr>::new(100).unwrap();
+    assert_eq!(domain.size, 128);
+
+    // evaluations from the FFT should match evaluating the polynomial at each domain element
+    let poly = DensePolynomial::rand(99, &mut rng);
+    let evals = domain.fft(&poly).unwrap();
+    for (element, eval) in domain.elements().iter().zip(evals.iter()) {
+        assert_eq!(poly.evaluate(*element), *eval);
+        assert_eq!(domain.evaluate_vanishing_polynomial(*element), Fr::from(0u32));
+    }
+
+    // inverse FFT and interpolation over the domain should recover the coefficients
+    let mut padded_poly = poly.coeffs().to_vec();
+    padded_poly.resize(domain.size, Fr::from(0u32));
+    assert_eq!(domain.ifft(&evals).unwrap(), padded_poly);
+    assert_eq!(DensePolynomial::interpolate(&domain.elements(), &evals).unwrap(), poly);
+
+    // coset FFT should evaluate over the shifted domain and round trip
+    let offset = Fr::rand(&mut rng);
+    let coset_evals = domain.coset_fft(&poly, offset).unwrap();
+    assert_eq!(coset_evals[3], poly.evaluate(offset * domain.element(3)));
+    assert_eq!(domain.coset_ifft(&coset_evals, offset).unwrap(), padded_poly);
+
+    // FFT multiplication of large polynomials should match schoolbook multiplication
+    let other = DensePolynomial::rand(69, &mut rng);
+    let mut expected = vec![Fr::from(0u32); poly.len() + other.len() - 1];
+    for (i, coeff1) in poly.iter().enumerate() {
+        for (j, coeff2) in other.iter().enumerate() {
+            expected[i + j] += *coeff1 * coeff2;
+        }
+    }
+    assert_eq!(&poly * &other, DensePolynomial::from_coefficients_vec(expected));
+
+    // roots of unity for sizes that are not a power of two come from the next power of two
+    let omega = get_omega::<Fr>(6).unwrap();
+    assert_eq!(omega.pow([8u64]), Fr::from(1u32));
+    assert_ne!(omega.pow([4u64]), Fr::from(1u32));
+    assert!(matches!(get_omega::<Fr>((1 << 32) + 1), Err(KzgError::DegreeTooLarge { .. })));
+
+    println!("Evaluation domain verified!");
+}
+
+pub fn test_dense_polynomial() {
+    let mut rng = ark_std::test_rng();
+
+    // trailing zeros are dropped, so the degree is that of the last non-zero coefficient
+    let zero = Fr::from(0u32);
+    let poly = DensePolynomial::from_coefficients_vec(vec![Fr::from(3u32), Fr::from(2u32), zero, zero]);
+    assert_eq!(poly.degree(), 1);
+    assert_eq!(poly.coeffs().len(), 2);
+    assert!(DensePolynomial::<Fr>::from_coefficients_slice(&[zero, zero]).is_empty());
+    assert!((poly.clone() + -poly.clone()).is_empty());
+
+    // long division returns both quotient and remainder, with p = q * d + r and deg(r) < deg(d)
+    let p = DensePolynomial::rand(10, &mut rng);
+    let d = DensePolynomial::rand(3, &mut rng);
+    let (q, remainder) = p.divide_with_remainder(&d).unwrap();
+    assert_eq!(q.degree(), 7);
+    assert!(remainder.degree() < d.degree());
+    assert_eq!(&q * &d + remainder.clone(), p);
+    let (q, r) = d.divide_with_remainder(&p).unwrap();
+    assert!(q.is_empty());
+    assert_eq!(r, d);
+    assert_eq!(p.divide_with_remainder(&DensePolynomial::from_coefficients_vec(vec![zero])), Err(KzgError::DivisionByZero));
+
+    // the zero polynomial of some points vanishes exactly on them, and its derivative does not
+    let points: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+    let zero_poly = DensePolynomial::from_roots(&points);
+    assert_eq!(zero_poly.degree(), 4);
+    for &point in &points {
+        assert_eq!(zero_poly.evaluate(point), zero);
+        assert_ne!(zero_poly.derivative().evaluate(point), zero);
+    }
+
+    // the remainder by the zero polynomial interpolates the polynomial on the points
+    let values: Vec<Fr> = points.iter().map(|&point| p.evaluate(point)).collect();
+    assert_eq!(p.divide_with_remainder(&zero_poly).unwrap().1, DensePolynomial::interpolate(&points, &values).unwrap());
+
+    // derivative of X^3 + 2 X is 3 X^2 + 2, and composition evaluates the inner polynomial first
+    let cubic = DensePolynomial::from_coefficients_vec(vec![zero, Fr::from(2u32), zero, Fr::from(1u32)]);
+    assert_eq!(cubic.derivative(), DensePolynomial::from_coefficients_vec(vec![Fr::from(2u32), zero, Fr::from(3u32)]));
+    let point = Fr::rand(&mut rng);
+    assert_eq!(cubic.compose(&d).evaluate(point), cubic.evaluate(d.evaluate(point)));
+    assert_eq!(cubic.compose(&d).degree(), 9);
+
+    // operators agree with evaluation
+    let sum: DensePolynomial<Fr> = [p.clone(), d.clone(), -cubic.clone()].into_iter().sum();
+    assert_eq!(sum.evaluate(point), p.evaluate(point) + d.evaluate(point) - cubic.evaluate(point));
+    assert_eq!((&p * &d * point).evaluate(point), p.evaluate(point) * d.evaluate(point) * point);
+
+    println!("Dense polynomials verified!");
+}
+
+pub fn test_padded_vector(secret: Fr) {
+    // vector of length 11 is padded to a domain of size 16
+    let mut rng = ark_std::test_rng();
+    let asvc_instance = ASVC::<Bls12_381>::key_gen(
+        G1::rand(&mut rng),
+        G2::rand(&mut rng),
+        11,
+        secret
+    ).unwrap();
+    assert_eq!(asvc_instance.domain.size, 16);
+
+    let vector: Vec<Fr> = (0..11).map(|_| Fr::rand(&mut rng)).collect();
+    let commitment = asvc_instance.vector_commit(&vector).unwrap();
+
+    // prove, aggregate and verify positions including the last one
+    let selected_indices = [10, 3, 7];
+    let subvector: Vec<Fr> = selected_indices.iter().map(|&index| vector[index]).collect();
+    let pi = asvc_instance.prove_position(&selected_indices, &vector).unwrap();
+    assert!(asvc_instance.verify_position(commitment, &selected_indices, &subvector, pi).unwrap());
+    let proofs: Vec<Proof<Bls12_381>> = selected_indices.iter()
+        .map(|&index| asvc_instance.prove_position(&[index], &vector).unwrap())
+        .collect();
+    assert_eq!(asvc_instance.aggregate_proofs(&selected_indices, proofs).unwrap(), pi);
+
+    // every position is proven at once, without proofs for the padding
+    let all_proofs = asvc_instance.prove_all_positions(&vector, &asvc_instance.fk20_precomputation().unwrap()).unwrap();
+    assert_eq!(all_proofs.len(), 11);
+    assert_eq!(all_proofs[10], asvc_instance.prove_position(&[10], &vector).unwrap());
+
+    // update the vector and keep the proof of the last position fresh
+    let mut pi_last = asvc_instance.prove_position(&[10], &vector).unwrap();
+    let mut updated_vector = vector.clone();
+    let mut commitment = commitment;
+    for index in [4, 10] {
+        let delta = Fr::rand(&mut rng);
+        updated_vector[index] += delta;
+        commitment = asvc_instance.update_commitment(commitment, index, delta).unwrap();
+        pi_last = asvc_instance.update_proof(pi_last, 10, index, delta).unwrap();
+    }
+    assert_eq!(commitment, asvc_instance.vector_commit(&updated_vector).unwrap());
+    assert!(asvc_instance.verify_position(commitment, &[10], &[updated_vector[10]], pi_last).unwrap());
+
+    // positions in the padding are not part of the vector
+    assert!(matches!(asvc_instance.prove_position(&[11], &vector), Err(KzgError::IndexOutOfRange { .. })));
+
+    println!("Padded vector verified!");
+}
+
+pub fn test_trusted_setup(kzg_instance: &KZG<Bls12_381>) {
+    let mut rng = ark_std::test_rng();
+
+    // write the powers of tau of an existing CRS in the raw format and load them back
+    let setup = TrustedSetup::<Bls12_381>::new(kzg_instance.crs_g1.clone(), kzg_instance.crs_g2.clone()).unwrap();
+    let path = std::env::temp_dir().join("kzg-rust-trusted-setup.bin");
+    std::fs::write(&path, setup.to_bytes()).unwrap();
+    let loaded_setup = TrustedSetup::<Bls12_381>::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // an instance built from the loaded setup commits, opens and verifies without the secret
+    let kzg_from_setup = KZG::from_trusted_setup(&loaded_setup).unwrap();
+    let poly = DensePolynomial::rand(kzg_from_setup.degree, &mut rng);
+    let commitment = kzg_from_setup.commit(&poly).unwrap();
+    assert_eq!(commitment, kzg_instance.commit(&poly).unwrap());
+    let point = Fr::rand(&mut rng);
+    let pi = kzg_from_setup.open(&poly, point).unwrap();
+    assert!(kzg_from_setup.verify(point, poly.evaluate(point), commitment, pi).unwrap());
+
+    // the text format of the Ethereum ceremony: counts, Lagrange G1 (only parsed), G2 and monomial G1 points
+    let g1_lines: Vec<String> = setup.g1_powers.iter().map(to_hex).collect();
+    let g2_lines: Vec<String> = setup.g2_powers.iter().map(to_hex).collect();
+    let lines = [
+        vec![g1_lines.len().to_string(), g2_lines.len().to_string()],
+        g1_lines.clone(),
+        g2_lines,
+        g1_lines
+    ].concat();
+    let txt_setup = TrustedSetup::<Bls12_381>::from_txt(&lines.join("\n")).unwrap();
+    assert_eq!(txt_setup.g1_powers, setup.g1_powers);
+    assert_eq!(txt_setup.g2_powers, setup.g2_powers);
+
+    // counts in the header that do not match the lines of the file are rejected, however large they are
+    let g1_line = to_hex(&setup.g1_powers[0]);
+    for contents in [format!("1\n1000000000000000000\n{}\n", g1_line), format!("{}\n1\n{}", usize::MAX, g1_line)] {
+        assert!(matches!(TrustedSetup::<Bls12_381>::from_txt(&contents), Err(KzgError::InvalidEncoding(_))));
+    }
+    let mut truncated_lines = lines.clone();
+    truncated_lines.pop();
+    assert!(matches!(TrustedSetup::<Bls12_381>::from_txt(&truncated_lines.join("\n")), Err(KzgError::InvalidEncoding(_))));
+
+    // an ASVC instance built from the setup proves and verifies positions
+    let asvc_instance = ASVC::from_trusted_setup(&txt_setup, 8).unwrap();
+    let vector: Vec<Fr> = (0..8).map(|_| Fr::rand(&mut rng)).collect();
+    let commitment = asvc_instance.vector_commit(&vector).unwrap();
+    let pi = asvc_instance.prove_position(&[5, 1], &vector).unwrap();
+    assert!(asvc_instance.verify_position(commitment, &[5, 1], &[vector[5], vector[1]], pi).unwrap());
+
+    // powers that are not consistent are rejected
+    let mut tampered_g1_powers = setup.g1_powers.clone();
+    tampered_g1_powers.swap(2, 3);
+    assert!(matches!(
+        TrustedSetup::<Bls12_381>::new(tampered_g1_powers, setup.g2_powers.clone()),
+        Err(KzgError::InvalidSetup(_))
+    ));
+
+    println!("Trusted setup verified!");
+}
+
+pub fn test_ceremony() {
+    let mut rng = ark_std::test_rng();
+
+    // three participants contribute to a CRS of degree 16 in sequence
+    let mut ceremony = Ceremony::<Bls12_381>::new(17, 17).unwrap();
+    assert!(ceremony.verify().is_err());
+    for _ in 0..3 {
+        ceremony.contribute(&mut rng);
+    }
+    ceremony.verify().unwrap();
+
+    // the resulting powers work as a KZG setup
+    let kzg_instance = KZG::from_trusted_setup(&ceremony.setup).unwrap();
+    let poly = DensePolynomial::rand(16, &mut rng);
+    let commitment = kzg_instance.commit(&poly).unwrap();
+    let point = Fr::rand(&mut rng);
+    let pi = kzg_instance.open(&poly, point).unwrap();
+    assert!(kzg_instance.verify(point, poly.evaluate(point), commitment, pi).unwrap());
+
+    // a forged proof of knowledge is rejected
+    let mut forged_contributions = ceremony.contributions.clone();
+    forged_contributions[1].pok_response += Fr::from(1u32);
+    let forged_ceremony = Ceremony {
+        setup: TrustedSetup::new(ceremony.setup.g1_powers.clone(), ceremony.setup.g2_powers.clone()).unwrap(),
+        contributions: forged_contributions
+    };
+    assert!(matches!(forged_ceremony.verify(), Err(KzgError::InvalidSetup(_))));
+
+    // powers that do not match the last contribution are rejected
+    let mut replaced_ceremony = Ceremony::<Bls12_381>::new(17, 17).unwrap();
+    replaced_ceremony.contribute(&mut rng);
+    let replaced_ceremony = Ceremony {
+        setup: replaced_ceremony.setup,
+        contributions: ceremony.contributions.clone()
+    };
+    assert!(matches!(replaced_ceremony.verify(), Err(KzgError::InvalidSetup(_))));
+
+    println!("Ceremony verified!");
+}
+
+pub fn test_polynomial_commitment(degree: usize) {
+    let mut rng = ark_std::test_rng();
+
+    // polynomials in coefficient form opened at random points, and vectors opened at positions
+    let poly: Vec<Fr> = (0..degree + 1).map(|_| Fr::rand(&mut rng)).collect();
+    let points: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+    run_polynomial_commitment::<KZG<Bls12_381>>(degree, &poly, &points);
+    let vector: Vec<Fr> = (0..degree).map(|_| Fr::rand(&mut rng)).collect();
+    run_polynomial_commitment::<ASVC<Bls12_381>>(degree, &vector, &[0, 7, degree - 1]);
+
+    // keys cannot be trimmed beyond the setup
+    let kzg_instance = <KZG<Bls12_381> as PolynomialCommitment>::setup(degree, &mut rng).unwrap();
+    assert!(matches!(kzg_instance.trim(degree + 1), Err(KzgError::DegreeTooLarge { .. })));
+    let (ck, _) = kzg_instance.trim(degree / 2).unwrap();
+    assert!(matches!(ck.commit(&poly), Err(KzgError::DegreeTooLarge { .. })));
+
+    println!("Polynomial commitment trait verified!");
+}
+
+// a protocol written once over any commitment scheme: commit, open at every point and check the openings
+// one by one and together, with at least two points
+fn run_polynomial_commitment<P: PolynomialCommitment>(max_degree: usize, poly: &P::Polynomial, points: &[P::Point])
+where
+    P::Point: Clone,
+    P::Value: Clone,
+    P::Commitment: Clone,
+{
+    let mut rng = ark_std::test_rng();
+    let params = P::setup(max_degree, &mut rng).unwrap();
+    let (ck, vk) = params.trim(max_degree).unwrap();
+
+    let commitment = P::commit(&ck, poly).unwrap();
+    let values: Vec<P::Value> = points.iter().map(|point| P::evaluate(poly, point).unwrap()).collect();
+    let proofs: Vec<P::Proof> = points.iter().map(|point| P::open(&ck, poly, point).unwrap()).collect();
+    for ((point, value), proof) in points.iter().zip(&values).zip(&proofs) {
+        assert!(P::check(&vk, &commitment, point, value, proof).unwrap());
+    }
+    let commitments = vec![commitment.clone(); points.len()];
+    assert!(P::batch_check(&vk, &commitments, points, &values, &proofs).unwrap());
+
+    // the value of another point does not verify
+    assert!(!P::check(&vk, &commitment, &points[0], &values[1], &proofs[0]).unwrap());
+    let mut swapped_values = values.clone();
+    swapped_values.swap(0, 1);
+    assert!(!P::batch_check(&vk, &commitments, points, &swapped_values, &proofs).unwrap());
+}
+
+pub fn test_serialization(
+    kzg_instance: &KZG<Bls12_381>,
+    asvc_instance: &ASVC<Bls12_381>,
+    vector: &[Fr]
+) {
+    let mut rng = ark_std::test_rng();
+
+    // a KZG instance round trips in both compressed and uncompressed form
+    let mut compressed = Vec::new();
+    kzg_instance.serialize_compressed(&mut compressed).unwrap();
+    let mut uncompressed = Vec::new();
+    kzg_instance.serialize_uncompressed(&mut uncompressed).unwrap();
+    assert!(compressed.len() < uncompressed.len());
+    let kzg_from_compressed = KZG::<Bls12_381>::deserialize_compressed(compressed.as_slice()).unwrap();
+    let kzg_from_uncompressed = KZG::<Bls12_381>::deserialize_uncompressed(uncompressed.as_slice()).unwrap();
+    assert_eq!(kzg_from_compressed.crs_g1, kzg_instance.crs_g1);
+    assert_eq!(kzg_from_uncompressed.crs_g2, kzg_instance.crs_g2);
+
+    // commitments and proofs round trip through hex and still verify
+    let poly = DensePolynomial::rand(kzg_instance.degree, &mut rng);
+    let point = Fr::rand(&mut rng);
+    let commitment: Commitment<Bls12_381> = from_hex(&to_hex(&kzg_instance.commit(&poly).unwrap())).unwrap();
+    let pi: Proof<Bls12_381> = from_hex(&to_hex(&kzg_instance.open(&poly, point).unwrap())).unwrap();
+    assert!(kzg_from_compressed.verify(point, poly.evaluate(point), commitment, pi).unwrap());
+
+    // ASVC keys round trip and the restored instance proves positions against the original keys
+    let mut bytes = Vec::new();
+    asvc_instance.serialize_compressed(&mut bytes).unwrap();
+    let restored_asvc = ASVC::<Bls12_381>::deserialize_compressed(bytes.as_slice()).unwrap();
+    assert_eq!(restored_asvc.domain.size, asvc_instance.domain.size);
+    let verification_key = from_hex(&to_hex(&asvc_instance.verification_key)).unwrap();
+    let restored_asvc = ASVC { verification_key, ..restored_asvc };
+    let commitment = restored_asvc.vector_commit(vector).unwrap();
+    let pi = restored_asvc.prove_position(&[2, 5], vector).unwrap();
+    assert!(asvc_instance.verify_position(commitment, &[2, 5], &[vector[2], vector[5]], pi).unwrap());
+
+    // truncated bytes, trailing bytes and malformed hex are rejected
+    assert!(KZG::<Bls12_381>::deserialize_compressed(&compressed[..compressed.len() - 1]).is_err());
+    let mut trailing = to_hex(&commitment);
+    trailing.push_str("00");
+    assert!(matches!(from_hex::<G1>(&trailing), Err(KzgError::InvalidEncoding(_))));
+    assert!(matches!(from_hex::<G1>("0xzz"), Err(KzgError::InvalidEncoding(_))));
+
+    // well-formed bytes of structures whose parts do not fit together are rejected
+    let mut inconsistent_kzg = kzg_instance.clone();
+    inconsistent_kzg.degree += 50;
+    assert!(from_hex::<KZG<Bls12_381>>(&to_hex(&inconsistent_kzg)).is_err());
+    let mut inconsistent_kzg = kzg_instance.clone();
+    inconsistent_kzg.crs_g2.truncate(1);
+    assert!(from_hex::<KZG<Bls12_381>>(&to_hex(&inconsistent_kzg)).is_err());
+    let (mut committer_key, _) = kzg_instance.trim(8).unwrap();
+    committer_key.degree = 12;
+    assert!(from_hex::<CommitterKey<Bls12_381>>(&to_hex(&committer_key)).is_err());
+    let mut inconsistent_asvc = asvc_instance.clone();
+    inconsistent_asvc.proving_key.li_commitment.truncate(4);
+    assert!(from_hex::<ASVC<Bls12_381>>(&to_hex(&inconsistent_asvc)).is_err());
+    let mut inconsistent_asvc = asvc_instance.clone();
+    inconsistent_asvc.update_key.ui_commitment.pop();
+    assert!(from_hex::<ASVC<Bls12_381>>(&to_hex(&inconsistent_asvc)).is_err());
+    let verifier_key: VectorVerifierKey<Bls12_381> = from_hex(&to_hex(&asvc_instance.verifier_key(2).unwrap())).unwrap();
+    assert!(verifier_key.verify_position(commitment, &[2, 5], &[vector[2], vector[5]], pi).unwrap());
+    let mut inconsistent_verifier_key = verifier_key.clone();
+    inconsistent_verifier_key.crs.g1.push(inconsistent_verifier_key.crs.g1[0]);
+    assert!(from_hex::<VectorVerifierKey<Bls12_381>>(&to_hex(&inconsistent_verifier_key)).is_err());
+
+    // trusted setups and ceremonies are verified when they are deserialized
+    let mut ceremony = Ceremony::<Bls12_381>::new(8, 4).unwrap();
+    assert!(from_hex::<Ceremony<Bls12_381>>(&to_hex(&ceremony)).is_ok());
+    ceremony.contribute(&mut rng);
+    assert!(from_hex::<Ceremony<Bls12_381>>(&to_hex(&ceremony)).is_ok());
+    let setup: TrustedSetup<Bls12_381> = from_hex(&to_hex(&ceremony.setup)).unwrap();
+    assert_eq!(setup.g1_powers, ceremony.setup.g1_powers);
+    ceremony.setup.g1_powers.swap(2, 3);
+    assert!(from_hex::<Ceremony<Bls12_381>>(&to_hex(&ceremony)).is_err());
+    assert!(from_hex::<TrustedSetup<Bls12_381>>(&to_hex(&ceremony.setup)).is_err());
+
+    // keys derived from a ceremony round trip, also with more powers in G2 than in G1
+    let mut ceremony = Ceremony::<Bls12_381>::new(4, 8).unwrap();
+    ceremony.contribute(&mut rng);
+    let kzg_from_ceremony = KZG::from_trusted_setup(&ceremony.setup).unwrap();
+    let restored_kzg: KZG<Bls12_381> = from_hex(&to_hex(&kzg_from_ceremony)).unwrap();
+    assert_eq!(restored_kzg.crs_g2, kzg_from_ceremony.crs_g2);
+    let poly = DensePolynomial::rand(2, &mut rng);
+    let commitment = kzg_from_ceremony.commit(&poly).unwrap();
+    let shifted_commitment = kzg_from_ceremony.prove_degree_bound(&poly, 3).unwrap();
+    assert!(restored_kzg.verify_degree_bound(commitment, 3, shifted_commitment).unwrap());
+
+    println!("Serialization verified!");
+}
+
+pub fn test_eip4844(secret: Fr) -> Eip4844 {
+    let mut rng = ark_std::test_rng();
+
+    // powers of tau for 4096 field elements, and 65 powers in G2 as in the Ethereum setup
+    let mut g1_powers = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
+    let mut g2_powers = Vec::with_capacity(FIELD_ELEMENTS_PER_CELL + 1);
+    let mut power = Fr::ONE;
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        g1_powers.push(G1Affine::generator() * power);
+        if i <= FIELD_ELEMENTS_PER_CELL {
+            g2_powers.push(G2Affine::generator() * power);
+        }
+        power *= secret;
+    }
+    let setup = TrustedSetup::<Bls12_381>::new(G1::normalize_batch(&g1_powers), G2::normalize_batch(&g2_powers)).unwrap();
+    let eip4844 = Eip4844::from_trusted_setup(&setup).unwrap();
+
+    // the domain is generated by the primitive root of unity 7 of the consensus specs
+    let mut exponent = Fr::MODULUS;
+    exponent.divn(12);
+    assert_eq!(eip4844.domain.omega, Fr::from(7u32).pow(exponent));
+
+    // compressed points use the ZCash encoding
+    assert_eq!(
+        to_hex(&G1Affine::generator()),
+        "0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+    );
+
+    // random blobs with their commitments and blob proofs
+    let random_blob = |rng: &mut _| -> Vec<u8> {
+        (0..FIELD_ELEMENTS_PER_BLOB).flat_map(|_| bls_field_to_bytes(Fr::rand(rng))).collect()
+    };
+    let blobs: Vec<Vec<u8>> = (0..3).map(|_| random_blob(&mut rng)).collect();
+    let commitments: Vec<_> = blobs.iter().map(|blob| eip4844.blob_to_kzg_commitment(blob).unwrap()).collect();
+    let proofs: Vec<_> = blobs.iter().zip(&commitments)
+        .map(|(blob, commitment)| eip4844.compute_blob_kzg_proof(blob, commitment).unwrap())
+        .collect();
+    for ((blob, commitment), proof) in blobs.iter().zip(&commitments).zip(&proofs) {
+        assert!(eip4844.verify_blob_kzg_proof(blob, commitment, proof).unwrap());
+    }
+    assert!(eip4844.verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs).unwrap());
+    assert!(eip4844.verify_blob_kzg_proof_batch(&[] as &[Vec<u8>], &[] as &[[u8; 48]], &[] as &[[u8; 48]]).unwrap());
+
+    // a proof of one blob does not verify another, alone or in a batch
+    assert!(!eip4844.verify_blob_kzg_proof(&blobs[0], &commitments[0], &proofs[1]).unwrap());
+    let swapped_proofs = [proofs[1], proofs[0], proofs[2]];
+    assert!(!eip4844.verify_blob_kzg_proof_batch(&blobs, &commitments, &swapped_proofs).unwrap());
+
+    // evaluation proofs outside and inside the domain, the blob stores the evaluations in bit-reversed order
+    let z = bls_field_to_bytes(Fr::rand(&mut rng));
+    let (proof, y) = eip4844.compute_kzg_proof(&blobs[0], &z).unwrap();
+    assert!(eip4844.verify_kzg_proof(&commitments[0], &z, &y, &proof).unwrap());
+    let z = bls_field_to_bytes(eip4844.domain.element(512));
+    let (proof, y) = eip4844.compute_kzg_proof(&blobs[0], &z).unwrap();
+    assert_eq!(y.as_slice(), &blobs[0][4 * 32..5 * 32]);
+    assert!(eip4844.verify_kzg_proof(&commitments[0], &z, &y, &proof).unwrap());
+
+    // malformed inputs are rejected
+    let mut non_canonical_blob = blobs[0].clone();
+    non_canonical_blob[..32].copy_from_slice(&Fr::MODULUS.to_bytes_be());
+    assert!(matches!(eip4844.blob_to_kzg_commitment(&non_canonical_blob), Err(KzgError::InvalidEncoding(_))));
+    assert!(matches!(
+        eip4844.blob_to_kzg_commitment(&blobs[0][..BYTES_PER_BLOB - 1]),
+        Err(KzgError::LengthMismatch { .. })
+    ));
+    let mut invalid_commitment = commitments[0];
+    invalid_commitment[47] ^= 1;
+    assert!(eip4844.verify_blob_kzg_proof(&blobs[0], &invalid_commitment, &proofs[0]).is_err());
+
+    // the point at infinity is only accepted in its canonical encoding, without junk or sort flag bits
+    let mut infinity = [0u8; 48];
+    infinity[0] = 0xc0;
+    assert!(bytes_to_kzg_commitment(&infinity).unwrap().is_zero());
+    let mut junk_infinity = infinity;
+    junk_infinity[47] = 1;
+    let mut sorted_infinity = infinity;
+    sorted_infinity[0] |= 0x20;
+    for encoding in [junk_infinity, sorted_infinity] {
+        assert!(matches!(bytes_to_kzg_commitment(&encoding), Err(KzgError::InvalidEncoding(_))));
+        assert!(eip4844.verify_blob_kzg_proof(&blobs[0], &encoding, &proofs[0]).is_err());
+        assert!(eip4844.verify_blob_kzg_proof(&blobs[0], &commitments[0], &encoding).is_err());
+    }
+    assert!(matches!(
+        eip4844.verify_blob_kzg_proof_batch(&blobs, &commitments[..2], &proofs),
+        Err(KzgError::LengthMismatch { .. })
+    ));
+
+    println!("EIP-4844 blobs verified!");
+    eip4844
+}
+
+pub fn test_eip7594(eip4844: &Eip4844) {
+    let mut rng = ark_std::test_rng();
+
+    // extend two random blobs into cells with their proofs
+    let blobs: Vec<Vec<u8>> = (0..2)
+        .map(|_| (0..FIELD_ELEMENTS_PER_BLOB).flat_map(|_| bls_field_to_bytes(Fr::rand(&mut rng))).collect())
+        .collect();
+    let commitments: Vec<_> = blobs.iter().map(|blob| eip4844.blob_to_kzg_commitment(blob).unwrap()).collect();
+    let (cells, proofs) = eip4844.compute_cells_and_kzg_proofs(&blobs[0]).unwrap();
+    assert_eq!(cells.len(), CELLS_PER_EXT_BLOB);
+
+    // the extension is systematic: the first half of the cells is the blob itself
+    assert_eq!(cells[..CELLS_PER_EXT_BLOB / 2].concat(), blobs[0]);
+
+    // cells of both blobs verify in a single batch, tampered cells and proofs do not
+    let (other_cells, other_proofs) = eip4844.compute_cells_and_kzg_proofs(&blobs[1]).unwrap();
+    let cell_indices = [0, 5, 127, 64, 5];
+    let batch_commitments = [commitments[0], commitments[0], commitments[0], commitments[1], commitments[1]];
+    let mut batch_cells = vec![cells[0], cells[5], cells[127], other_cells[64], other_cells[5]];
+    let batch_proofs = [proofs[0], proofs[5], proofs[127], other_proofs[64], other_proofs[5]];
+    assert!(eip4844.verify_cell_kzg_proof_batch(&batch_commitments, &cell_indices, &batch_cells, &batch_proofs).unwrap());
+    assert!(!eip4844.verify_cell_kzg_proof_batch(&batch_commitments, &[0, 5, 127, 64, 6], &batch_cells, &batch_proofs).unwrap());
+    batch_cells[2][31] ^= 1;
+    assert!(!eip4844.verify_cell_kzg_proof_batch(&batch_commitments, &cell_indices, &batch_cells, &batch_proofs).unwrap());
+
+    // any half of the cells recovers all cells and proofs
+    let mut known_indices: Vec<usize> = (0..CELLS_PER_EXT_BLOB).choose_multiple(&mut rng, CELLS_PER_EXT_BLOB / 2);
+    known_indices.sort();
+    let known_cells: Vec<_> = known_indices.iter().map(|&index| cells[index]).collect();
+    let (recovered_cells, recovered_proofs) = eip4844.recover_cells_and_kzg_proofs(&known_indices, &known_cells).unwrap();
+    assert!(recovered_cells == cells);
+    assert_eq!(recovered_proofs, proofs);
+
+    // fewer than half of the cells, duplicate or unsorted indices and indices outside the extension are rejected
+    assert!(matches!(
+        eip4844.recover_cells_and_kzg_proofs(&known_indices[1..], &known_cells[1..]),
+        Err(KzgError::LengthMismatch { .. })
+    ));
+    let mut duplicate_indices = known_indices.clone();
+    duplicate_indices[1] = duplicate_indices[0];
+    assert_eq!(eip4844.recover_cells_and_kzg_proofs(&duplicate_indices, &known_cells), Err(KzgError::DuplicatePoints));
+    let mut unsorted_indices = known_indices.clone();
+    unsorted_indices.swap(0, 1);
+    let mut unsorted_cells = known_cells.clone();
+    unsorted_cells.swap(0, 1);
+    assert!(matches!(
+        eip4844.recover_cells_and_kzg_proofs(&unsorted_indices, &unsorted_cells),
+        Err(KzgError::InvalidEncoding(_))
+    ));
+    assert!(matches!(
+        eip4844.verify_cell_kzg_proof_batch(&[commitments[0]], &[CELLS_PER_EXT_BLOB], &[cells[0]], &[proofs[0]]),
+        Err(KzgError::IndexOutOfRange { .. })
+    ));
+
+    // cell proofs with a non-canonical encoding of the point at infinity are rejected
+    let mut sorted_infinity = [0u8; 48];
+    sorted_infinity[0] = 0xe0;
+    assert!(eip4844.verify_cell_kzg_proof_batch(&[commitments[0]], &[0], &[cells[0]], &[sorted_infinity]).is_err());
+
+    println!("EIP-7594 cells verified!");
+}
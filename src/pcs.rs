@@ -0,0 +1,176 @@
+//! Common interface of the commitment schemes, so that protocols can be written once over any of them.
+//! Points and values are whatever the scheme opens: field elements for KZG, and vector positions with
+//! their values for ASVC.
+
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::UniformRand;
+use ark_std::rand::RngCore;
+use crate::asvc::{VectorVerifierKey, ASVC};
+use crate::commitment::{Commitment, Proof};
+use crate::error::KzgError;
+use crate::kzg::{CommitterKey, VerifierKey, KZG};
+use crate::polynomial::DensePolynomial;
+
+pub trait PolynomialCommitment: Sized {
+    type CommitterKey;
+    type VerifierKey;
+    type Polynomial: ?Sized;
+    type Point;
+    type Value;
+    type Commitment;
+    type Proof;
+
+    // public parameters supporting up to max_degree, generated from a random secret that is discarded
+    fn setup<R: RngCore>(max_degree: usize, rng: &mut R) -> Result<Self, KzgError>;
+
+    // keys for committing to and verifying polynomials up to max_degree
+    fn trim(&self, max_degree: usize) -> Result<(Self::CommitterKey, Self::VerifierKey), KzgError>;
+
+    fn commit(ck: &Self::CommitterKey, poly: &Self::Polynomial) -> Result<Self::Commitment, KzgError>;
+
+    // value of the polynomial at a point, which open proves
+    fn evaluate(poly: &Self::Polynomial, point: &Self::Point) -> Result<Self::Value, KzgError>;
+
+    fn open(ck: &Self::CommitterKey, poly: &Self::Polynomial, point: &Self::Point) -> Result<Self::Proof, KzgError>;
+
+    fn check(
+        vk: &Self::VerifierKey,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &Self::Value,
+        proof: &Self::Proof
+    ) -> Result<bool, KzgError>;
+
+    // check openings of possibly different commitments at possibly different points
+    fn batch_check(
+        vk: &Self::VerifierKey,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        values: &[Self::Value],
+        proofs: &[Self::Proof]
+    ) -> Result<bool, KzgError> {
+        check_batch_lengths(commitments.len(), points.len(), values.len(), proofs.len())?;
+        for (((commitment, point), value), proof) in commitments.iter().zip(points).zip(values).zip(proofs) {
+            if !Self::check(vk, commitment, point, value, proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl <E: Pairing> PolynomialCommitment for KZG<E> {
+    type CommitterKey = CommitterKey<E>;
+    type VerifierKey = VerifierKey<E>;
+    type Polynomial = [E::ScalarField];
+    type Point = E::ScalarField;
+    type Value = E::ScalarField;
+    type Commitment = Commitment<E>;
+    type Proof = Proof<E>;
+
+    fn setup<R: RngCore>(max_degree: usize, rng: &mut R) -> Result<Self, KzgError> {
+        let mut kzg = KZG::new(E::G1::generator(), E::G2::generator(), max_degree);
+        kzg.setup(E::ScalarField::rand(rng));
+        Ok(kzg)
+    }
+
+    fn trim(&self, max_degree: usize) -> Result<(Self::CommitterKey, Self::VerifierKey), KzgError> {
+        KZG::trim(self, max_degree)
+    }
+
+    fn commit(ck: &Self::CommitterKey, poly: &Self::Polynomial) -> Result<Self::Commitment, KzgError> {
+        ck.commit(poly)
+    }
+
+    fn evaluate(poly: &Self::Polynomial, point: &Self::Point) -> Result<Self::Value, KzgError> {
+        Ok(DensePolynomial::from_coefficients_slice(poly).evaluate(*point))
+    }
+
+    fn open(ck: &Self::CommitterKey, poly: &Self::Polynomial, point: &Self::Point) -> Result<Self::Proof, KzgError> {
+        ck.open(poly, *point)
+    }
+
+    fn check(
+        vk: &Self::VerifierKey,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &Self::Value,
+        proof: &Self::Proof
+    ) -> Result<bool, KzgError> {
+        vk.verify(*point, *value, *commitment, *proof)
+    }
+
+    // all openings are checked with a single multi-pairing
+    fn batch_check(
+        vk: &Self::VerifierKey,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        values: &[Self::Value],
+        proofs: &[Self::Proof]
+    ) -> Result<bool, KzgError> {
+        check_batch_lengths(commitments.len(), points.len(), values.len(), proofs.len())?;
+        let openings: Vec<_> = (0..commitments.len())
+            .map(|i| (commitments[i], points[i], values[i], proofs[i]))
+            .collect();
+        vk.verify_batch(&openings)
+    }
+}
+
+impl <E: Pairing> PolynomialCommitment for ASVC<E> {
+    type CommitterKey = ASVC<E>;
+    type VerifierKey = VectorVerifierKey<E>;
+    type Polynomial = [E::ScalarField];
+    type Point = usize;
+    type Value = E::ScalarField;
+    type Commitment = Commitment<E>;
+    type Proof = Proof<E>;
+
+    // keys for vectors of max_degree positions
+    fn setup<R: RngCore>(max_degree: usize, rng: &mut R) -> Result<Self, KzgError> {
+        ASVC::key_gen(E::G1::generator(), E::G2::generator(), max_degree, E::ScalarField::rand(rng))
+    }
+
+    // keys for vectors of max_degree positions, derived again from the CRS, and a verifier key for
+    // single positions
+    fn trim(&self, max_degree: usize) -> Result<(Self::CommitterKey, Self::VerifierKey), KzgError> {
+        let trimmed = if max_degree == self.degree {
+            self.clone()
+        } else {
+            ASVC::from_crs(self.proving_key.crs.clone(), max_degree)?
+        };
+        let verifier_key = trimmed.verifier_key(1)?;
+        Ok((trimmed, verifier_key))
+    }
+
+    fn commit(ck: &Self::CommitterKey, poly: &Self::Polynomial) -> Result<Self::Commitment, KzgError> {
+        ck.vector_commit(poly)
+    }
+
+    fn evaluate(poly: &Self::Polynomial, point: &Self::Point) -> Result<Self::Value, KzgError> {
+        poly.get(*point).copied().ok_or(KzgError::IndexOutOfRange { index: *point, length: poly.len() })
+    }
+
+    fn open(ck: &Self::CommitterKey, poly: &Self::Polynomial, point: &Self::Point) -> Result<Self::Proof, KzgError> {
+        ck.prove_position(&[*point], poly)
+    }
+
+    fn check(
+        vk: &Self::VerifierKey,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &Self::Value,
+        proof: &Self::Proof
+    ) -> Result<bool, KzgError> {
+        vk.verify_position(*commitment, &[*point], &[*value], *proof)
+    }
+}
+
+// check that every opening of a batch has a commitment, point, value and proof
+fn check_batch_lengths(commitments: usize, points: usize, values: usize, proofs: usize) -> Result<(), KzgError> {
+    for actual in [points, values, proofs] {
+        if actual != commitments {
+            return Err(KzgError::LengthMismatch { expected: commitments, actual });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,331 @@
+//! Polynomials in coefficient form. Coefficients are stored from the constant term up and are always
+//! normalized, i.e. without trailing zeros, so the zero polynomial has no coefficients and the degree
+//! is the index of the last one. A polynomial dereferences to its coefficients, so it can be passed
+//! wherever a coefficient slice is expected (e.g. to commit it).
+
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Deref, Mul, MulAssign, Neg, Sub, SubAssign};
+use ark_ff::{FftField, Field, Zero};
+use ark_std::rand::RngCore;
+use crate::error::KzgError;
+use crate::utils::EvaluationDomain;
+
+// polynomials with at least this many coefficients are multiplied with FFTs
+const FFT_MUL_THRESHOLD: usize = 64;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DensePolynomial<F: Field> {
+    coeffs: Vec<F>
+}
+
+impl <F: Field> DensePolynomial<F> {
+    pub fn from_coefficients_vec(coeffs: Vec<F>) -> Self {
+        let mut poly = Self { coeffs };
+        poly.normalize();
+        poly
+    }
+
+    pub fn from_coefficients_slice(coeffs: &[F]) -> Self {
+        Self::from_coefficients_vec(coeffs.to_vec())
+    }
+
+    // constant polynomial c
+    pub fn constant(c: F) -> Self {
+        Self::from_coefficients_vec(vec![c])
+    }
+
+    // product of (X - root) over the roots, e.g. the zero polynomial of a set of points
+    pub fn from_roots(roots: &[F]) -> Self {
+        let mut coeffs = vec![F::ONE];
+        for &root in roots {
+            // multiply by X - root in place
+            coeffs.push(F::ZERO);
+            for i in (1..coeffs.len()).rev() {
+                coeffs[i] = coeffs[i - 1] - root * coeffs[i];
+            }
+            coeffs[0] *= -root;
+        }
+        Self { coeffs }
+    }
+
+    // random polynomial of the given degree (its leading coefficient is non-zero with overwhelming probability)
+    pub fn rand<R: RngCore>(degree: usize, rng: &mut R) -> Self {
+        Self::from_coefficients_vec((0..degree + 1).map(|_| F::rand(rng)).collect())
+    }
+
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    pub fn into_coeffs(self) -> Vec<F> {
+        self.coeffs
+    }
+
+    // degree of the polynomial, where the zero polynomial has degree 0
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    // evaluate the polynomial at a point (Horner's method)
+    pub fn evaluate(&self, point: F) -> F {
+        let mut value = F::ZERO;
+        for coeff in self.coeffs.iter().rev() {
+            value = value * point + coeff;
+        }
+        value
+    }
+
+    // quotient and remainder of long division, where the remainder has a lower degree than the divisor
+    pub fn divide_with_remainder(&self, divisor: &Self) -> Result<(Self, Self), KzgError> {
+        if divisor.is_zero() {
+            return Err(KzgError::DivisionByZero);
+        }
+        if self.coeffs.len() < divisor.coeffs.len() {
+            return Ok((Self::zero(), self.clone()));
+        }
+
+        let mut quotient = vec![F::ZERO; self.coeffs.len() - divisor.coeffs.len() + 1];
+        let mut remainder = self.coeffs.clone();
+        let leading_inv = divisor.coeffs.last().unwrap().inverse().unwrap();
+
+        // cancel the leading coefficient of the remainder until it is shorter than the divisor
+        for pos in (0..quotient.len()).rev() {
+            let coeff = remainder[pos + divisor.coeffs.len() - 1] * leading_inv;
+            quotient[pos] = coeff;
+            for (i, &factor) in divisor.coeffs.iter().enumerate() {
+                remainder[pos + i] -= factor * coeff;
+            }
+        }
+        remainder.truncate(divisor.coeffs.len() - 1);
+
+        Ok((Self::from_coefficients_vec(quotient), Self::from_coefficients_vec(remainder)))
+    }
+
+    // formal derivative, sum i c_i X^(i-1)
+    pub fn derivative(&self) -> Self {
+        let coeffs = self.coeffs.iter().enumerate().skip(1)
+            .map(|(i, &coeff)| coeff * F::from(i as u64))
+            .collect();
+        Self::from_coefficients_vec(coeffs)
+    }
+
+    // remove trailing zero coefficients
+    fn normalize(&mut self) {
+        while self.coeffs.last().is_some_and(|coeff| coeff.is_zero()) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+impl <F: FftField> DensePolynomial<F> {
+    // Lagrange interpolation of the values at the points
+    pub fn interpolate(points: &[F], values: &[F]) -> Result<Self, KzgError> {
+        if points.len() != values.len() {
+            return Err(KzgError::LengthMismatch { expected: points.len(), actual: values.len() });
+        }
+
+        // points that are exactly the elements of a domain are interpolated with an inverse FFT
+        if let Some(domain) = EvaluationDomain::<F>::from_elements(points) {
+            return Ok(Self::from_coefficients_vec(domain.ifft(values)?));
+        }
+
+        let mut result = Self::zero();
+        for i in 0..points.len() {
+            let mut denominator = F::ONE;
+            for j in 0..points.len() {
+                if i != j {
+                    denominator *= points[i] - points[j];
+                }
+            }
+
+            // denominator is zero only if the points are not distinct
+            let denominator_inv = denominator.inverse().ok_or(KzgError::DuplicatePoints)?;
+            let others: Vec<F> = points.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, &point)| point).collect();
+            result += &(Self::from_roots(&others) * (values[i] * denominator_inv));
+        }
+
+        Ok(result)
+    }
+
+    // composition p(q(X)), by Horner's method over polynomials
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut result = Self::zero();
+        for &coeff in self.coeffs.iter().rev() {
+            result = &(&result * other) + &Self::constant(coeff);
+        }
+        result
+    }
+
+    // product of two polynomials, pointwise in evaluation form if both are large
+    fn naive_or_fft_mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+
+        let len = self.coeffs.len() + other.coeffs.len() - 1;
+        if std::cmp::min(self.coeffs.len(), other.coeffs.len()) >= FFT_MUL_THRESHOLD {
+            if let Ok(domain) = EvaluationDomain::<F>::new(len) {
+                let evals1 = domain.fft(&self.coeffs).unwrap();
+                let evals2 = domain.fft(&other.coeffs).unwrap();
+                let evals: Vec<F> = evals1.iter().zip(&evals2).map(|(&x, &y)| x * y).collect();
+                let mut coeffs = domain.ifft(&evals).unwrap();
+                coeffs.truncate(len);
+                return Self::from_coefficients_vec(coeffs);
+            }
+        }
+
+        let mut coeffs = vec![F::ZERO; len];
+        for (i, &coeff1) in self.coeffs.iter().enumerate() {
+            for (j, &coeff2) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] += coeff1 * coeff2;
+            }
+        }
+        Self::from_coefficients_vec(coeffs)
+    }
+}
+
+impl <F: Field> Deref for DensePolynomial<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        &self.coeffs
+    }
+}
+
+impl <F: Field> AsRef<[F]> for DensePolynomial<F> {
+    fn as_ref(&self) -> &[F] {
+        &self.coeffs
+    }
+}
+
+impl <F: Field> Zero for DensePolynomial<F> {
+    fn zero() -> Self {
+        Self { coeffs: vec![] }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+}
+
+impl <'a, F: Field> Add<&'a DensePolynomial<F>> for &'a DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn add(self, other: &'a DensePolynomial<F>) -> DensePolynomial<F> {
+        let mut result = self.clone();
+        result += other;
+        result
+    }
+}
+
+impl <'a, F: Field> Sub<&'a DensePolynomial<F>> for &'a DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn sub(self, other: &'a DensePolynomial<F>) -> DensePolynomial<F> {
+        let mut result = self.clone();
+        result -= other;
+        result
+    }
+}
+
+impl <'a, F: FftField> Mul<&'a DensePolynomial<F>> for &'a DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn mul(self, other: &'a DensePolynomial<F>) -> DensePolynomial<F> {
+        self.naive_or_fft_mul(other)
+    }
+}
+
+impl <F: Field> Add for DensePolynomial<F> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += &other;
+        self
+    }
+}
+
+impl <F: Field> Sub for DensePolynomial<F> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= &other;
+        self
+    }
+}
+
+impl <F: FftField> Mul for DensePolynomial<F> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.naive_or_fft_mul(&other)
+    }
+}
+
+impl <F: Field> Neg for DensePolynomial<F> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        for coeff in self.coeffs.iter_mut() {
+            *coeff = -*coeff;
+        }
+        self
+    }
+}
+
+// multiplication by a scalar
+impl <F: Field> Mul<F> for DensePolynomial<F> {
+    type Output = Self;
+
+    fn mul(mut self, scalar: F) -> Self {
+        self *= scalar;
+        self
+    }
+}
+
+impl <F: Field> Mul<F> for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn mul(self, scalar: F) -> DensePolynomial<F> {
+        self.clone() * scalar
+    }
+}
+
+impl <'a, F: Field> AddAssign<&'a DensePolynomial<F>> for DensePolynomial<F> {
+    fn add_assign(&mut self, other: &'a DensePolynomial<F>) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), F::ZERO);
+        }
+        for (coeff, &other_coeff) in self.coeffs.iter_mut().zip(&other.coeffs) {
+            *coeff += other_coeff;
+        }
+        self.normalize();
+    }
+}
+
+impl <'a, F: Field> SubAssign<&'a DensePolynomial<F>> for DensePolynomial<F> {
+    fn sub_assign(&mut self, other: &'a DensePolynomial<F>) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), F::ZERO);
+        }
+        for (coeff, &other_coeff) in self.coeffs.iter_mut().zip(&other.coeffs) {
+            *coeff -= other_coeff;
+        }
+        self.normalize();
+    }
+}
+
+impl <F: Field> MulAssign<F> for DensePolynomial<F> {
+    fn mul_assign(&mut self, scalar: F) {
+        for coeff in self.coeffs.iter_mut() {
+            *coeff *= scalar;
+        }
+        self.normalize();
+    }
+}
+
+impl <F: Field> Sum for DensePolynomial<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, poly| acc + poly)
+    }
+}
@@ -0,0 +1,153 @@
+//! Powers of tau produced by an external ceremony, so that no secret is ever held in memory.
+
+use std::path::Path;
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate};
+use crate::encoding::decode_hex;
+use crate::error::KzgError;
+
+// deserialization checks that the powers are consistent, like `new`
+#[derive(Clone, CanonicalSerialize)]
+pub struct TrustedSetup<E: Pairing> {
+    // [tau^i]_1 for i = 0..g1_powers.len()
+    pub g1_powers: Vec<E::G1Affine>,
+    // [tau^i]_2 for i = 0..g2_powers.len()
+    pub g2_powers: Vec<E::G2Affine>
+}
+
+impl <E: Pairing> TrustedSetup<E> {
+    // build a setup from powers of tau, checking that they are consistent
+    pub fn new(g1_powers: Vec<E::G1Affine>, g2_powers: Vec<E::G2Affine>) -> Result<Self, KzgError> {
+        let setup = Self { g1_powers, g2_powers };
+        setup.verify()?;
+        Ok(setup)
+    }
+
+    // load a setup from a file, either in the text format of the Ethereum KZG ceremony
+    // (trusted_setup.txt) or in the raw compressed format of `to_bytes`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, KzgError> {
+        let bytes = std::fs::read(path).map_err(|e| KzgError::Io(e.to_string()))?;
+        match std::str::from_utf8(&bytes) {
+            Ok(contents) if contents.trim_start().starts_with(|c: char| c.is_ascii_digit()) => {
+                Self::from_txt(contents)
+            }
+            _ => Self::from_bytes(&bytes),
+        }
+    }
+
+    // parse the text format of the Ethereum KZG ceremony: the number of G1 and G2 points, followed by
+    // the hex encoded compressed G1 points in Lagrange form, the G2 points in monomial form and the
+    // G1 points in monomial form (only the monomial points are used)
+    pub fn from_txt(contents: &str) -> Result<Self, KzgError> {
+        let lines: Vec<&str> = contents.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        if lines.len() < 2 {
+            return Err(KzgError::InvalidEncoding("unexpected end of file".to_string()));
+        }
+        let num_g1: usize = lines[0].parse().map_err(|_| KzgError::InvalidEncoding("invalid number of G1 points".to_string()))?;
+        let num_g2: usize = lines[1].parse().map_err(|_| KzgError::InvalidEncoding("invalid number of G2 points".to_string()))?;
+
+        // the counts come from the file, so they are checked against its lines before anything is read
+        let num_points = num_g1.checked_mul(2).and_then(|num_points| num_points.checked_add(num_g2));
+        if num_points != Some(lines.len() - 2) {
+            return Err(KzgError::InvalidEncoding(format!(
+                "header announces {} G1 and {} G2 points but the file has {} point lines",
+                num_g1, num_g2, lines.len() - 2
+            )));
+        }
+        let (lagrange_lines, rest) = lines[2..].split_at(num_g1);
+        let (g2_lines, g1_lines) = rest.split_at(num_g2);
+
+        // Lagrange form G1 points are only checked to be valid encodings
+        for line in lagrange_lines {
+            E::G1Affine::deserialize_compressed(decode_hex(line)?.as_slice())?;
+        }
+        let g2_powers = g2_lines.iter()
+            .map(|line| Ok(E::G2Affine::deserialize_compressed(decode_hex(line)?.as_slice())?))
+            .collect::<Result<Vec<_>, KzgError>>()?;
+        let g1_powers = g1_lines.iter()
+            .map(|line| Ok(E::G1Affine::deserialize_compressed(decode_hex(line)?.as_slice())?))
+            .collect::<Result<Vec<_>, KzgError>>()?;
+
+        Self::new(g1_powers, g2_powers)
+    }
+
+    // parse the raw format, i.e. the compressed canonical serialization: the G1 powers followed by the
+    // G2 powers, each as a little-endian u64 length followed by the points
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        // the points are deserialized separately so that inconsistent powers are reported by `new`
+        let mut reader = bytes;
+        let g1_powers = Vec::<E::G1Affine>::deserialize_compressed(&mut reader)?;
+        let g2_powers = Vec::<E::G2Affine>::deserialize_compressed(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(KzgError::InvalidEncoding("trailing bytes".to_string()));
+        }
+
+        Self::new(g1_powers, g2_powers)
+    }
+
+    // write the setup in the raw format read by `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    // check that both vectors are powers of the same tau, i.e. e([tau^(i+1)]_1, [1]_2) = e([tau^i]_1, [tau]_2)
+    // for every i and likewise for G2. Each chain is combined with random coefficients into a single check.
+    // Subgroup membership is checked when the points are deserialized.
+    pub fn verify(&self) -> Result<(), KzgError> {
+        if self.g1_powers.len() < 2 || self.g2_powers.len() < 2 {
+            return Err(KzgError::InvalidSetup("at least two G1 and G2 powers are required".to_string()));
+        }
+        if self.g1_powers[0].is_zero() || self.g2_powers[0].is_zero() || self.g1_powers[1].is_zero() {
+            return Err(KzgError::InvalidSetup("generators and tau must be non-zero".to_string()));
+        }
+
+        let mut rng = rand::thread_rng();
+
+        // sum r_i [tau^(i+1)]_1 paired with [1]_2 equals sum r_i [tau^i]_1 paired with [tau]_2
+        let r: Vec<E::ScalarField> = (1..self.g1_powers.len()).map(|_| E::ScalarField::rand(&mut rng)).collect();
+        let g1_shifted = E::G1::msm_unchecked(&self.g1_powers[1..], &r);
+        let g1_unshifted = E::G1::msm_unchecked(&self.g1_powers[..self.g1_powers.len() - 1], &r);
+        let g1_check = E::multi_pairing(
+            [g1_shifted.into_affine(), (-g1_unshifted).into_affine()],
+            [self.g2_powers[0], self.g2_powers[1]]
+        );
+
+        // [tau]_1 paired with sum r_i [tau^i]_2 equals [1]_1 paired with sum r_i [tau^(i+1)]_2
+        let r: Vec<E::ScalarField> = (1..self.g2_powers.len()).map(|_| E::ScalarField::rand(&mut rng)).collect();
+        let g2_unshifted = E::G2::msm_unchecked(&self.g2_powers[..self.g2_powers.len() - 1], &r);
+        let g2_shifted = E::G2::msm_unchecked(&self.g2_powers[1..], &r);
+        let g2_check = E::multi_pairing(
+            [self.g1_powers[1], (-self.g1_powers[0].into_group()).into_affine()],
+            [g2_unshifted.into_affine(), g2_shifted.into_affine()]
+        );
+
+        if !g1_check.is_zero() || !g2_check.is_zero() {
+            return Err(KzgError::InvalidSetup("powers are not consistent".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl <E: Pairing> Valid for TrustedSetup<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.g1_powers.check()?;
+        self.g2_powers.check()?;
+        self.verify().map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+impl <E: Pairing> CanonicalDeserialize for TrustedSetup<E> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let setup = Self {
+            g1_powers: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            g2_powers: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?
+        };
+        if validate == Validate::Yes {
+            setup.check()?;
+        }
+        Ok(setup)
+    }
+}
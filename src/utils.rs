@@ -1,131 +1,332 @@
-use ark_ff::{Field, PrimeField};
-use ark_std::log2;
+use std::ops::{Add, Mul, Sub};
+use ark_ff::{batch_inversion, FftField, Field, Zero};
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate, Write};
+use crate::error::KzgError;
+use crate::polynomial::DensePolynomial;
 
-// helper function for polynomial addition
-pub fn add<E:Field>(p1: &[E], p2: &[E]) -> Vec<E> {
-    let mut result = vec![E::ZERO; std::cmp::max(p1.len(), p2.len())];
+// helper function to get the primitive root of unity of the smallest power-of-two subgroup with at least size elements
+pub fn get_omega<E:FftField>(size: usize) -> Result<E, KzgError> {
+    let max_size = 1usize.checked_shl(E::TWO_ADICITY).unwrap_or(usize::MAX);
+    let size = std::cmp::max(size, 1).checked_next_power_of_two()
+        .filter(|&n| n.trailing_zeros() <= E::TWO_ADICITY)
+        .ok_or(KzgError::DegreeTooLarge { degree: size, max_degree: max_size })?;
 
-    for (i, &coeff) in p1.iter().enumerate() {
-        result[i] += coeff;
-    }
-    for (i, &coeff) in p2.iter().enumerate() {
-        result[i] += coeff;
+    // square the 2^TWO_ADICITY-th root of unity down to a root of unity of order size
+    let exp = size.trailing_zeros();
+    let mut omega = E::TWO_ADIC_ROOT_OF_UNITY;
+    for _ in exp..E::TWO_ADICITY {
+        omega.square_in_place();
     }
+    Ok(omega)
+}
 
-    result
+// helper function to commit to a polynomial with a CRS using (Pippenger) multi-scalar multiplication
+pub fn msm<G: CurveGroup>(crs: &[G::Affine], poly: &[G::ScalarField]) -> G {
+    let n = std::cmp::min(crs.len(), poly.len());
+    G::msm_unchecked(&crs[..n], &poly[..n])
 }
 
-// helper function for polynomial multiplication
-pub fn mul<E:Field>(p1: &[E], p2: &[E]) -> Vec<E> {
-    let mut result = vec![E::ZERO; p1.len() + p2.len() - 1];
+// FK20: all opening proofs of a polynomial over a domain, i.e. [(f(X) - f(w^i)) / (X - w^i)]_1 for every w^i,
+// in O(n log n). The proof at z is sum_j h_j z^j with h_j = sum_{i > j} f_i [tau^(i-j-1)], so the h_j are
+// a Toeplitz matrix-vector product, computed as a convolution of f with the reversed CRS, and all proofs
+// are the FFT of h over the domain.
+// More generally, the multiproofs over cosets of size l are [(f(X) - r(X)) / (X^l - s)]_1 for every s in
+// the domain, where r is the remainder of f by X^l - s. The quotient is sum_{t >= 1} s^(t-1) H_t with
+// H_t = sum_m f_(m+tl) [tau^m], so splitting m by its residue modulo l gives l Toeplitz products of size d/l.
+// The FFTs of the reversed CRS only depend on the CRS and the degree, so they are computed once, and all
+// proofs of a polynomial cost l scalar FFTs, one group IFFT and one group FFT
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fk20Precomputation<G: CurveGroup> {
+    // maximum degree of the polynomials and size of the cosets (1 for single point proofs)
+    pub degree: usize,
+    pub coset_size: usize,
+    convolution_domain: EvaluationDomain<G::ScalarField>,
+    // FFT of the reversed powers [tau^(al+r)]_1 for every residue r modulo the coset size
+    crs_evals: Vec<Vec<G>>
+}
 
-    for (i, &coeff1) in p1.iter().enumerate() {
-        for (j, &coeff2) in p2.iter().enumerate() {
-            result[i + j] += coeff1 * coeff2;
+impl <G: CurveGroup> Fk20Precomputation<G> {
+    // precompute for polynomials up to degree with a CRS of at least degree powers
+    pub fn new(crs: &[G::Affine], degree: usize, coset_size: usize) -> Result<Self, KzgError> {
+        if coset_size == 0 {
+            return Err(KzgError::EmptyInput);
         }
-    }
 
-    result
-}
+        // every residue polynomial has degree at most d = degree / l, and its Toeplitz product the d powers
+        // [tau^r]_1, [tau^(l+r)]_1, ..., [tau^((d-1)l+r)]_1
+        let d = degree / coset_size;
+        if crs.len() < d * coset_size {
+            return Err(KzgError::DegreeTooLarge { degree, max_degree: crs.len() });
+        }
+        let convolution_domain = EvaluationDomain::<G::ScalarField>::new(2 * d)?;
+        let mut crs_evals = Vec::new();
+        if d > 0 {
+            for residue in 0..coset_size {
+                let reversed_crs: Vec<G> = crs.iter().skip(residue).step_by(coset_size).take(d).rev()
+                    .map(|&point| point.into())
+                    .collect();
+                crs_evals.push(convolution_domain.group_fft(&reversed_crs)?);
+            }
+        }
 
-// helper function for polynomial division
-pub fn div<E:Field>(p1: &[E], p2: &[E]) -> Result<Vec<E>, &'static str> {
-    if p2.is_empty() || p2.iter().all(|&x| x == E::ZERO) {
-        return Err("Cannot divide by zero polynomial");
+        Ok(Self { degree, coset_size, convolution_domain, crs_evals })
     }
 
-    if p1.len() < p2.len() {
-        return Ok(vec![E::ZERO]);
+    // all proofs of the polynomial over the domain (over the cosets of the domain elements for l > 1)
+    pub fn proofs(&self, poly: &[G::ScalarField], domain: &EvaluationDomain<G::ScalarField>) -> Result<Vec<G>, KzgError> {
+        if poly.len() > self.degree + 1 {
+            return Err(KzgError::DegreeTooLarge { degree: poly.len() - 1, max_degree: self.degree });
+        }
+        // a polynomial of degree below l has zero quotients
+        if poly.len() <= self.coset_size {
+            return Ok(vec![G::zero(); domain.size]);
+        }
+
+        // H_(j+1) is the sum over the residues of coefficient d+j of the product of the residue polynomial
+        // with the reversed powers, and the products are summed before a single inverse FFT
+        let d = self.degree / self.coset_size;
+        let mut product_evals = vec![G::zero(); self.convolution_domain.size];
+        for (residue, crs_evals) in self.crs_evals.iter().enumerate() {
+            let residue_poly: Vec<G::ScalarField> = poly.iter().skip(residue).step_by(self.coset_size).copied().collect();
+            let poly_evals = self.convolution_domain.fft(&residue_poly)?;
+            for ((product, point), eval) in product_evals.iter_mut().zip(crs_evals).zip(&poly_evals) {
+                *product += *point * eval;
+            }
+        }
+        let product = self.convolution_domain.group_ifft(&product_evals)?;
+
+        // H_t is zero for t > deg(f) / l
+        domain.group_fft(&product[d..d + (poly.len() - 1) / self.coset_size])
     }
+}
 
-    let mut quotient = vec![E::ZERO; p1.len() - p2.len() + 1];
-    let mut remainder: Vec<E> = p1.to_vec();
+// radix-2 evaluation domain, i.e. the multiplicative subgroup of size 2^k generated by omega
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvaluationDomain<E: FftField> {
+    pub size: usize,
+    pub log_size: u32,
+    pub omega: E,
+    pub omega_inv: E,
+    pub size_inv: E,
+}
 
-    while remainder.len() >= p2.len() {
-        let coeff = *remainder.last().unwrap() / *p2.last().unwrap();
-        let pos = remainder.len() - p2.len();
+impl <E: FftField> EvaluationDomain<E> {
+    // smallest radix-2 domain with at least size elements
+    pub fn new(size: usize) -> Result<Self, KzgError> {
+        let omega = get_omega::<E>(size)?;
+        let size = std::cmp::max(size, 1).next_power_of_two();
+        let log_size = size.trailing_zeros();
 
-        quotient[pos] = coeff;
+        Ok(Self {
+            size,
+            log_size,
+            omega,
+            omega_inv: omega.inverse().unwrap(),
+            size_inv: E::from(size as u64).inverse().unwrap(),
+        })
+    }
 
-        for (i, &factor) in p2.iter().enumerate() {
-            remainder[pos + i] -= factor * coeff;
+    // domain whose elements are exactly the given points in order, if there is one
+    pub fn from_elements(points: &[E]) -> Option<Self> {
+        if points.is_empty() || !points.len().is_power_of_two() {
+            return None;
         }
+        let domain = Self::new(points.len()).ok()?;
+        let mut element = E::ONE;
+        for &point in points {
+            if point != element {
+                return None;
+            }
+            element *= domain.omega;
+        }
+        Some(domain)
+    }
 
-        while let Some(true) = remainder.last().map(|x| *x == E::ZERO) {
-            remainder.pop();
+    // i-th element of the domain, w^i
+    pub fn element(&self, i: usize) -> E {
+        self.omega.pow([i as u64])
+    }
+
+    // all elements of the domain, w^0, w^1, ..., w^(n-1)
+    pub fn elements(&self) -> Vec<E> {
+        let mut elements = Vec::with_capacity(self.size);
+        let mut element = E::ONE;
+        for _ in 0..self.size {
+            elements.push(element);
+            element *= self.omega;
         }
+        elements
     }
 
-    Ok(quotient)
-}
+    // vanishing polynomial of the domain, X^n - 1
+    pub fn vanishing_polynomial(&self) -> DensePolynomial<E> {
+        let mut coeffs = vec![E::ZERO; self.size + 1];
+        coeffs[0] = -E::ONE;
+        coeffs[self.size] = E::ONE;
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
 
-// helper function to evaluate polynomial at a point
-pub fn evaluate<E:Field>(poly: &[E], point: E) -> E {
-    let mut value = E::ZERO;
+    // evaluate the vanishing polynomial of the domain at a point
+    pub fn evaluate_vanishing_polynomial(&self, point: E) -> E {
+        point.pow([self.size as u64]) - E::ONE
+    }
 
-    for i in 0..poly.len() {
-        value += poly[i] * point.pow(&[i as u64]);
+    // evaluate the polynomial with the given evaluations over the domain (padded with zeros) at a point,
+    // using the barycentric formula p(z) = (z^n - 1) / n * sum_i p_i w^i / (z - w^i) outside the domain
+    pub fn evaluate_lagrange(&self, evals: &[E], point: E) -> Result<E, KzgError> {
+        let evals = self.padded(evals)?;
+        let elements = self.elements();
+        if let Some(i) = elements.iter().position(|&element| element == point) {
+            return Ok(evals[i]);
+        }
+
+        let mut denominators: Vec<E> = elements.iter().map(|&element| point - element).collect();
+        batch_inversion(&mut denominators);
+        let sum: E = evals.iter().zip(&elements).zip(&denominators)
+            .map(|((&eval, &element), &denominator_inv)| eval * element * denominator_inv)
+            .sum();
+        Ok(sum * self.evaluate_vanishing_polynomial(point) * self.size_inv)
     }
 
-    value
-}
+    // evaluations of a polynomial over the domain (forward NTT)
+    pub fn fft(&self, coeffs: &[E]) -> Result<Vec<E>, KzgError> {
+        let mut values = self.padded(coeffs)?;
+        fft_in_place(&mut values, self.omega);
+        Ok(values)
+    }
 
-// helper function to perform Lagrange interpolation given a set of points
-pub fn interpolate<E:Field>(points: &[E], values: &[E]) -> Result<Vec<E>, &'static str> {
-    if points.len() != values.len() {
-        return Err("Number of points and values do not match");
+    // coefficients of a polynomial from its evaluations over the domain (inverse NTT)
+    pub fn ifft(&self, evals: &[E]) -> Result<Vec<E>, KzgError> {
+        let mut values = self.padded(evals)?;
+        fft_in_place(&mut values, self.omega_inv);
+        for value in values.iter_mut() {
+            *value *= self.size_inv;
+        }
+        Ok(values)
     }
 
-    let mut result = vec![E::ZERO; points.len()];
+    // evaluations of a polynomial over the coset offset * domain
+    pub fn coset_fft(&self, coeffs: &[E], offset: E) -> Result<Vec<E>, KzgError> {
+        let mut shifted = self.padded(coeffs)?;
+        distribute_powers(&mut shifted, offset);
+        fft_in_place(&mut shifted, self.omega);
+        Ok(shifted)
+    }
 
-    for i in 0..points.len() {
-        let mut numerator = vec![E::ONE];
-        let mut denominator = E::ONE;
+    // coefficients of a polynomial from its evaluations over the coset offset * domain
+    pub fn coset_ifft(&self, evals: &[E], offset: E) -> Result<Vec<E>, KzgError> {
+        let mut coeffs = self.ifft(evals)?;
+        distribute_powers(&mut coeffs, offset.inverse().ok_or(KzgError::DivisionByZero)?);
+        Ok(coeffs)
+    }
 
-        for j in 0..points.len() {
-            if i == j {
-                continue;
-            }
+    // evaluations over the domain of a polynomial whose coefficients are group elements
+    pub fn group_fft<G: CurveGroup<ScalarField = E>>(&self, coeffs: &[G]) -> Result<Vec<G>, KzgError> {
+        let mut values = self.padded(coeffs)?;
+        fft_in_place(&mut values, self.omega);
+        Ok(values)
+    }
 
-            numerator = mul(&numerator, &[-points[j], E::ONE]);
-            denominator *= points[i] - points[j];
+    // group element coefficients of a polynomial from its evaluations over the domain
+    pub fn group_ifft<G: CurveGroup<ScalarField = E>>(&self, evals: &[G]) -> Result<Vec<G>, KzgError> {
+        let mut values = self.padded(evals)?;
+        fft_in_place(&mut values, self.omega_inv);
+        for value in values.iter_mut() {
+            *value *= self.size_inv;
         }
+        Ok(values)
+    }
 
-        let denominator_inv = denominator.inverse().unwrap();
-        let term: Vec<E> = numerator.iter().map(|&x| x * values[i] * denominator_inv).collect();
+    // copy values into a vector of the domain size, padded with zeros
+    fn padded<T: Clone + Zero>(&self, values: &[T]) -> Result<Vec<T>, KzgError> {
+        if values.len() > self.size {
+            return Err(KzgError::DegreeTooLarge { degree: values.len() - 1, max_degree: self.size - 1 });
+        }
+        let mut padded = values.to_vec();
+        padded.resize(self.size, T::zero());
+        Ok(padded)
+    }
+}
+
+// a domain is serialized as its size, all other fields are derived from it
+impl <E: FftField> CanonicalSerialize for EvaluationDomain<E> {
+    fn serialize_with_mode<W: Write>(&self, writer: W, compress: Compress) -> Result<(), SerializationError> {
+        (self.size as u64).serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        (self.size as u64).serialized_size(compress)
+    }
+}
 
-        result = add(&result, &term);
+impl <E: FftField> Valid for EvaluationDomain<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
     }
+}
 
-    Ok(result)
+impl <E: FftField> CanonicalDeserialize for EvaluationDomain<E> {
+    fn deserialize_with_mode<R: Read>(reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let size = u64::deserialize_with_mode(reader, compress, validate)? as usize;
+        if !size.is_power_of_two() {
+            return Err(SerializationError::InvalidData);
+        }
+        Self::new(size).map_err(|_| SerializationError::InvalidData)
+    }
 }
 
-// helper function to get the roots of unity of a polynomial
-pub fn get_omega<E:PrimeField>(coefficients: &[E]) -> E {
-    let mut coefficients = coefficients.to_vec();
-    let n = coefficients.len() - 1;
-    if !n.is_power_of_two() {
-        let num_coeffs = coefficients.len().checked_next_power_of_two().unwrap();
-        // pad the coefficients with zeros to the nearest power of two
-        for i in coefficients.len()..num_coeffs {
-            coefficients[i] = E::ZERO;
+// reorder values of power-of-two length so that index i moves to the index with its bits reversed
+pub fn bit_reverse_permutation<T>(values: &mut [T]) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+
+    let log_n = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
         }
     }
+}
 
-    let m = coefficients.len();
-    let exp = log2(m);
-    let mut omega = E::TWO_ADIC_ROOT_OF_UNITY;
-    for _ in exp..E::TWO_ADICITY {
-        omega.square_in_place();
+// multiply the i-th value by offset^i
+fn distribute_powers<E: Field>(values: &mut [E], offset: E) {
+    let mut power = E::ONE;
+    for value in values.iter_mut() {
+        *value *= power;
+        power *= offset;
     }
-    omega
 }
 
-// helper function to multiple a polynomial with a scalar value
-pub fn scalar_mul<E:Field>(poly: &[E], scalar: E) -> Vec<E> {
-    let mut result = Vec::with_capacity(poly.len());
-    for coeff in poly {
-        result.push(*coeff * scalar);
+// iterative radix-2 Cooley-Tukey FFT, the length of values must be a power of two and omega a root of unity of that order
+// (values are either field elements or group elements, which are multiplied by powers of omega)
+fn fft_in_place<E: Field, T>(values: &mut [T], omega: E)
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<E, Output = T>
+{
+    let n = values.len();
+    if n <= 1 {
+        return;
     }
-    result    
-}
\ No newline at end of file
+
+    bit_reverse_permutation(values);
+
+    // butterflies, merging pairs of sub-transforms of size m
+    let mut m = 1;
+    while m < n {
+        let omega_m = omega.pow([(n / (2 * m)) as u64]);
+        for k in (0..n).step_by(2 * m) {
+            let mut w = E::ONE;
+            for j in 0..m {
+                let t = values[k + j + m] * w;
+                values[k + j + m] = values[k + j] - t;
+                values[k + j] = values[k + j] + t;
+                w *= omega_m;
+            }
+        }
+        m *= 2;
+    }
+}
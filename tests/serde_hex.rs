@@ -0,0 +1,71 @@
+//! Round trip of keys, commitments and proofs through the `serde_hex` adapter.
+//!
+//! Usage: `cargo test --features serde --test serde_hex`
+
+#![cfg(feature = "serde")]
+
+use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+use ark_ec::Group;
+use ark_ff::UniformRand;
+use serde::{Deserialize, Serialize};
+use kzg_rust::{Commitment, Proof, VerifierKey, KZG};
+
+#[derive(Serialize, Deserialize)]
+struct Opening {
+    #[serde(with = "kzg_rust::encoding::serde_hex")]
+    commitment: Commitment<Bls12_381>,
+    #[serde(with = "kzg_rust::encoding::serde_hex")]
+    point: Fr,
+    #[serde(with = "kzg_rust::encoding::serde_hex")]
+    value: Fr,
+    #[serde(with = "kzg_rust::encoding::serde_hex")]
+    proof: Proof<Bls12_381>,
+    #[serde(with = "kzg_rust::encoding::serde_hex")]
+    verifier_key: VerifierKey<Bls12_381>
+}
+
+#[test]
+fn opening_round_trips_through_json() {
+    let mut rng = ark_std::test_rng();
+    let mut kzg = KZG::<Bls12_381>::new(G1::generator(), <Bls12_381 as ark_ec::pairing::Pairing>::G2::generator(), 8);
+    kzg.setup(Fr::rand(&mut rng));
+    let (committer_key, verifier_key) = kzg.trim(8).unwrap();
+
+    let poly: Vec<Fr> = (0..9).map(|_| Fr::rand(&mut rng)).collect();
+    let point = Fr::rand(&mut rng);
+    let opening = Opening {
+        commitment: committer_key.commit(&poly).unwrap(),
+        point,
+        value: kzg_rust::DensePolynomial::from_coefficients_slice(&poly).evaluate(point),
+        proof: committer_key.open(&poly, point).unwrap(),
+        verifier_key
+    };
+
+    // every field is a 0x prefixed hex string, and the restored opening still verifies
+    let json = serde_json::to_string(&opening).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value["commitment"].as_str().unwrap().starts_with("0x"));
+    assert_eq!(value["commitment"].as_str().unwrap().len(), 2 + 2 * 48);
+    let restored: Opening = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.commitment, opening.commitment);
+    assert_eq!(restored.proof, opening.proof);
+    assert!(restored.verifier_key.verify(restored.point, restored.value, restored.commitment, restored.proof).unwrap());
+}
+
+#[test]
+fn invalid_hex_is_rejected() {
+    // malformed hex, a point that is not on the curve and trailing bytes
+    let generator = kzg_rust::encoding::to_hex(&Commitment::<Bls12_381>(G1::generator()));
+    for hex in ["\"0xzz\"".to_string(), format!("\"0x{}\"", "11".repeat(48)), format!("\"{}00\"", generator)] {
+        let json = format!("{{\"value\": {}}}", hex);
+        assert!(serde_json::from_str::<Wrapper>(&json).is_err());
+    }
+    let json = format!("{{\"value\": \"{}\"}}", generator);
+    assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap().value, Commitment(G1::generator()));
+}
+
+#[derive(Deserialize)]
+struct Wrapper {
+    #[serde(with = "kzg_rust::encoding::serde_hex")]
+    value: Commitment<Bls12_381>
+}